@@ -0,0 +1,40 @@
+use std::fmt;
+
+// The kinds of content a manifest cid can resolve to
+// Manifests keep storing the content type as a string so existing state and clients are unaffected
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ContentType {
+    Ipfs,
+    Hyperfiles,
+    Json,
+    // Any other content type, kept verbatim
+    Other(String)
+}
+
+impl ContentType {
+    // Known content types are matched case-insensitively so `IPFS` and `ipfs` are stored the same way
+    // Empty strings and strings containing whitespace are rejected as they are almost always typos
+    pub fn parse(input: &str) -> Option<ContentType> {
+        if input.is_empty() || input.chars().any(char::is_whitespace) {
+            return None;
+        }
+
+        return Some(match input.to_ascii_lowercase().as_str() {
+            "ipfs" => ContentType::Ipfs,
+            "hyperfiles" => ContentType::Hyperfiles,
+            "json" => ContentType::Json,
+            _ => ContentType::Other(input.to_string()),
+        });
+    }
+}
+
+impl fmt::Display for ContentType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return match self {
+            ContentType::Ipfs => write!(f, "ipfs"),
+            ContentType::Hyperfiles => write!(f, "hyperfiles"),
+            ContentType::Json => write!(f, "json"),
+            ContentType::Other(other) => write!(f, "{other}"),
+        };
+    }
+}
@@ -0,0 +1,119 @@
+use std::fmt;
+
+use near_sdk::FunctionError;
+
+// Failures of the mutating registry methods, each variant reverts with the message the registry has always used
+// so integrators can branch on the variant while existing clients keep matching on the text
+#[derive(Debug, Clone, PartialEq, FunctionError)]
+pub enum RegistryError {
+    ContractPaused,
+    NoPackagesFound,
+    PackageNotFound,
+    InvalidPackageName,
+    VersionNotFound,
+    VersionExists,
+    VersionLimitReached,
+    InvalidVersion,
+    EmptyTag,
+    TagTooLong,
+    TagNotFound,
+    InvalidCid,
+    // The reason the owner gave for blocking the cid
+    BlockedCid(String),
+    MissingContentType,
+    EmptyContentType,
+    InvalidContentType,
+    InvalidEncryption,
+    TooManyDependencies,
+    NotAllowedToPublish,
+    UnregisteredContract,
+    EmptyBatch,
+    // The maximum number of manifests the owner allows per batch
+    BatchTooLarge(u32),
+    SelfMerge,
+    MergeConflict,
+    PackageNameTaken,
+    NoPendingTransfer,
+    UndoWindowElapsed,
+    NotAuthorOrOwner,
+    AttestorNotFound,
+    NoteTooLong,
+    DuplicateAttestation,
+    InvalidAttestationSignature,
+    MissingManifestSignature,
+    InvalidManifestSignature,
+    IndirectKeyRegistration,
+    UnsupportedKeyType,
+    MissingAttestorIdentifier,
+    NotCalledByContract,
+    SelfAttestation,
+    AttestationIndexOutOfRange,
+    NoPendingChallenge,
+    InvalidChallengeSignature,
+    // The yoctoNEAR needed to cover the storage written by the call
+    InsufficientDeposit(u128)
+}
+
+impl fmt::Display for RegistryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return match self {
+            RegistryError::ContractPaused => write!(f, "Contract is paused"),
+            RegistryError::NoPackagesFound => write!(f, "No packages found for account_id"),
+            RegistryError::PackageNotFound => write!(f, "Package name not found for given author"),
+            RegistryError::InvalidPackageName => write!(
+                f,
+                "Package name must be 1 to 64 characters of lowercase letters, digits, '.', '_' or '-'"
+            ),
+            RegistryError::VersionNotFound => write!(f, "Version not found for package"),
+            RegistryError::VersionExists => write!(f, "Version already exists for this package"),
+            RegistryError::VersionLimitReached => write!(f, "Version limit reached"),
+            RegistryError::InvalidVersion => write!(f, "Version is not valid semver"),
+            RegistryError::EmptyTag => write!(f, "Tag cannot be empty"),
+            RegistryError::TagTooLong => write!(f, "Tag exceeds the maximum length"),
+            RegistryError::TagNotFound => write!(f, "Tag not found for package"),
+            RegistryError::InvalidCid => write!(f, "Invalid IPFS cid"),
+            RegistryError::BlockedCid(reason) => write!(f, "Cid is blocked: {reason}"),
+            RegistryError::MissingContentType => write!(f, "No content_type provided and no default set for author"),
+            RegistryError::EmptyContentType => write!(f, "Default content_type cannot be empty"),
+            RegistryError::InvalidContentType => write!(f, "Content type must be non-empty and contain no whitespace"),
+            RegistryError::InvalidEncryption => write!(f, "Encryption scheme and key_ref must both be set"),
+            RegistryError::TooManyDependencies => write!(f, "Manifest declares too many dependencies"),
+            RegistryError::NotAllowedToPublish => write!(f, "Signer is not allowed to publish for this author"),
+            RegistryError::UnregisteredContract => write!(f, "Contract is not registered to publish as an author"),
+            RegistryError::EmptyBatch => write!(f, "No manifests provided"),
+            RegistryError::BatchTooLarge(max) => write!(f, "Batch exceeds the maximum of {max} manifests"),
+            RegistryError::SelfMerge => write!(f, "Cannot merge a package into itself"),
+            RegistryError::MergeConflict => write!(f, "Version already exists in target package"),
+            RegistryError::PackageNameTaken => write!(f, "New owner already has a package with this name"),
+            RegistryError::NoPendingTransfer => write!(f, "No transfer of this package is pending for the signer"),
+            RegistryError::UndoWindowElapsed => write!(f, "The latest version can no longer be undone"),
+            RegistryError::NotAuthorOrOwner => write!(f, "Only the author or the owner can purge an author's data"),
+            RegistryError::AttestorNotFound => write!(f, "Attestor not found"),
+            RegistryError::NoteTooLong => write!(f, "Attestation note exceeds the maximum length"),
+            RegistryError::DuplicateAttestation => write!(f, "Duplicate attestation"),
+            RegistryError::InvalidAttestationSignature => write!(f, "Invalid attestation signature"),
+            RegistryError::MissingManifestSignature => write!(f, "Manifest signature required by the author's registered key"),
+            RegistryError::InvalidManifestSignature => write!(f, "Invalid manifest signature"),
+            RegistryError::IndirectKeyRegistration => write!(f, "Author keys must be registered directly by the author account"),
+            RegistryError::UnsupportedKeyType => write!(f, "Only ed25519 keys are supported"),
+            RegistryError::MissingAttestorIdentifier => write!(f, "Contract attestations require an identifier"),
+            RegistryError::NotCalledByContract => write!(f, "Contract attestations must come from a cross-contract call"),
+            RegistryError::SelfAttestation => write!(f, "Authors cannot attest to their own packages"),
+            RegistryError::AttestationIndexOutOfRange => write!(f, "Attestation index out of range"),
+            RegistryError::NoPendingChallenge => write!(f, "No pending challenge for attestor"),
+            RegistryError::InvalidChallengeSignature => write!(f, "Invalid challenge signature"),
+            RegistryError::InsufficientDeposit(required) => {
+                write!(f, "Attached deposit must cover {required} yoctoNEAR of storage")
+            }
+        };
+    }
+}
+
+// Result counterpart of require!, returns the error when the condition does not hold
+pub fn ensure(condition: bool, error: RegistryError) -> Result<(), RegistryError> {
+    if !condition {
+        return Err(error);
+    }
+
+    return Ok(());
+}
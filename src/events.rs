@@ -0,0 +1,58 @@
+use near_sdk::env::log_str;
+use near_sdk::serde::Serialize;
+use near_sdk::serde_json;
+use near_sdk::AccountId;
+
+// NEP-297 standard name and version that indexers can filter registry events by
+pub const EVENT_STANDARD: &str = "attestation-registry";
+pub const EVENT_STANDARD_VERSION: &str = "1.0.0";
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ManifestEventData {
+    pub author: AccountId,
+    pub package_name: String,
+    pub version: String,
+    pub cid: String
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AttestationEventData {
+    pub attestor: AccountId,
+    pub author: AccountId,
+    pub package_name: String,
+    pub cid: String
+}
+
+// Every event the registry emits, serialized as the NEP-297 `event` and `data` fields
+#[derive(Serialize, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(tag = "event", content = "data", rename_all = "snake_case")]
+pub enum RegistryEvent {
+    ManifestCreated(Vec<ManifestEventData>),
+    ManifestUpdated(Vec<ManifestEventData>),
+    AttestationCreated(Vec<AttestationEventData>)
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+struct EventLog<'a> {
+    standard: &'static str,
+    version: &'static str,
+    #[serde(flatten)]
+    event: &'a RegistryEvent
+}
+
+impl RegistryEvent {
+    // Logs the event with the EVENT_JSON prefix expected by NEP-297 indexers
+    pub fn emit(&self) {
+        let log = EventLog {
+            standard: EVENT_STANDARD,
+            version: EVENT_STANDARD_VERSION,
+            event: self
+        };
+
+        log_str(&format!("EVENT_JSON:{}", serde_json::to_string(&log).unwrap()));
+    }
+}
@@ -1,21 +1,55 @@
 use near_sdk::borsh::{BorshDeserialize, BorshSerialize};
 use near_sdk::env::log_str;
 use near_sdk::serde::{Serialize, Deserialize};
-use near_sdk::collections::{LookupMap, UnorderedMap};
+use near_sdk::collections::{LookupMap, LookupSet, UnorderedMap, Vector};
+use near_sdk::json_types::U128;
 use near_sdk::near_bindgen;
 use near_sdk::{
-    AccountId, BorshStorageKey, PublicKey, require
+    AccountId, BorshStorageKey, CurveType, FunctionError, NearToken, Promise, PublicKey, require
 };
 
+mod content_type;
+mod errors;
+mod events;
+mod merkle;
+mod migrate;
+mod semver;
+use content_type::ContentType;
+use errors::{ensure, RegistryError};
+use events::{AttestationEventData, ManifestEventData, RegistryEvent};
+use merkle::MerkleTree;
+use semver::{Requirement, Version};
+
 // Represents the content being stored into the storage map
-#[derive(BorshSerialize, BorshDeserialize, Clone, Debug)]
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Debug, PartialEq)]
 #[borsh(crate = "near_sdk::borsh")]
-
+#[serde(crate = "near_sdk::serde")]
 pub struct Manifest {
     pub version: String,
     pub cid: String,
     pub content_type: String,
-    pub types: Vec<String>
+    pub types: Vec<String>,
+    // Describes how the content is encrypted when the cid points at an encrypted blob
+    pub encryption: Option<EncryptionInfo>,
+    // Block timestamp in nanoseconds at which the version was published
+    pub created_at: u64,
+    // Block timestamp in nanoseconds of the last update_manifest call, zero if never updated
+    pub updated_at: u64,
+    // Yanked versions stay resolvable but are passed over when choosing the latest version
+    pub yanked: bool,
+    // Number of downloads reported through record_download
+    pub downloads: u64,
+    // Packages this version depends on, see Dependency
+    pub dependencies: Vec<Dependency>
+}
+
+// The scheme used to encrypt a manifest's content and a reference to the key needed to decrypt it
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[borsh(crate = "near_sdk::borsh")]
+#[serde(crate = "near_sdk::serde")]
+pub struct EncryptionInfo {
+    pub scheme: String,
+    pub key_ref: String
 }
 
 // An attestation for a given manifest
@@ -23,18 +57,147 @@ pub struct Manifest {
 #[borsh(crate = "near_sdk::borsh")]
 #[serde(crate = "near_sdk::serde")]
 pub struct Attestation {
-    pub pubkey: PublicKey,
-    pub cid: String
+    // The signer's access key, absent for attestations made by a contract
+    pub pubkey: Option<PublicKey>,
+    // An identifier supplied by a contract attestor, e.g. the pipeline or job that produced the claim
+    pub identifier: Option<String>,
+    pub cid: String,
+    // A short justification left by the attestor alongside the claim
+    pub note: Option<String>,
+    // Block timestamp of the last answered liveness challenge, zero if never reconfirmed
+    pub reconfirmed_at_ns: u64,
+    // The kind of claim being made, e.g. "build-provenance" or "security-audit"
+    pub claim_type: String,
+    // The version of the schema the attestation data at cid follows
+    pub schema_version: String,
+    // Block timestamp in nanoseconds after which the claim is no longer valid, zero if it never expires
+    pub expires_at: u64
+}
+
+// A single sibling hash on the path from a leaf to the package root
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ProofStep {
+    pub hash: String,
+    // True when the sibling is hashed on the left of the running node
+    pub is_left: bool
+}
+
+// An inclusion proof for a manifest against the root returned by get_package_root
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct MerkleProof {
+    // Hex encoded sha256 of the borsh encoded manifest with its download count zeroed
+    pub leaf: String,
+    // Position of the manifest in publish order
+    pub index: u64,
+    pub steps: Vec<ProofStep>
+}
+
+// How version strings of a package are validated when publishing
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[borsh(crate = "near_sdk::borsh")]
+#[serde(crate = "near_sdk::serde")]
+pub enum VersioningScheme {
+    // Any version string is accepted
+    Freeform,
+    // Versions must follow major.minor.patch with an optional prerelease and build
+    Semver
+}
+
+// Which logs the registry writes, set by the owner with set_log_level
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[borsh(crate = "near_sdk::borsh")]
+#[serde(crate = "near_sdk::serde")]
+pub enum LogLevel {
+    // Nothing is logged, not even NEP-297 events
+    Off,
+    // Only NEP-297 events are logged
+    Events,
+    // NEP-297 events and free-text progress logs
+    Verbose
+}
+
+// Settings and limits the owner controls, returned as a whole by get_config
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[borsh(crate = "near_sdk::borsh")]
+#[serde(crate = "near_sdk::serde")]
+pub struct Config {
+    // Upper bound on the number of manifests batch_create_manifests accepts in one call, zero means unlimited
+    pub max_batch_size: u32,
+    // While paused every mutating entrypoint reverts and views stay available
+    pub paused: bool,
+    // Upper bound on the number of versions a package may hold, zero means unlimited
+    pub max_versions_per_package: u32,
+    // Registry wide switch for authors attesting to their own packages, disallowed unless the owner opts in
+    pub self_attestation_allowed: bool,
+    // Number of cids currently blocked with block_cid
+    pub blocked_cids: u64,
+    // Nanoseconds after publishing during which an author may still take a version back with undo_last_publish
+    pub undo_window_ns: u64,
+    // Which logs mutating methods write
+    pub log_level: LogLevel
+}
+
+// Whether a version can be used, returned by resolve_status
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum VersionStatus {
+    // The version is published and not yanked
+    Active,
+    // The version is published but its author yanked it
+    Yanked,
+    // The author, package or version does not exist
+    NotFound
+}
+
+// How merge_packages resolves a version that exists in both packages
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum MergeStrategy {
+    // Keep the version already in the target package
+    Skip,
+    // Replace the target's version with the one being merged in
+    Overwrite,
+    // Revert the whole merge
+    Error
 }
 
 #[derive(BorshDeserialize, BorshStorageKey, BorshSerialize, Copy, Clone)]
 #[borsh(crate = "near_sdk::borsh")]
 enum PrefixKeys {
+    // Package, Manifest and Attestation hold the layout before per-account prefixes and are only read by migrate
     Package,
     Manifest,
     Attestation,
     Types,
-    TypeList
+    TypeList,
+    DefaultContentType,
+    SelfAttestation,
+    PackageRoot,
+    PublishAllowlist,
+    Challenge,
+    AttestorAttestations,
+    PackageNames,
+    VersioningScheme,
+    PackageAttestors,
+    ContractPublishers,
+    PackageAuthors,
+    CidOrigins,
+    PackageIndex,
+    IndexedPackages,
+    AuthorKeys,
+    AuthorAttestations,
+    Tags,
+    BlockedCids,
+    IndexedAttestors,
+    PackageTransfers,
+    PackagesV2,
+    ManifestV2,
+    AttestationsV2,
+    PackageTags,
+    PackageTrees,
+    PackageTreeNodes
 }
 
 pub type PackageName = String;
@@ -42,6 +205,44 @@ pub type Namespace = Vec<u8>;
 pub type Releases = LookupMap<PackageName, Vec<Manifest>>;
 pub type Attestations = Vec<Attestation>;
 pub type Types = Vec<String>;
+// The author, package name and version requirement of a package a manifest depends on
+pub type Dependency = (AccountId, PackageName, String);
+// The author, package name and version a manifest cid was published under
+pub type CidOrigin = (AccountId, PackageName, String);
+
+// Upper bound on the number of bytes an attestation note may contain
+pub const MAX_NOTE_LENGTH: usize = 280;
+
+// Upper bound on the number of characters a package name may contain
+pub const MAX_PACKAGE_NAME_LENGTH: usize = 64;
+
+// Upper bound on the number of dependencies a manifest may declare
+pub const MAX_DEPENDENCIES: usize = 32;
+
+// Upper bound on the number of bytes a package tag may contain
+pub const MAX_TAG_LENGTH: usize = 32;
+
+// Number of manifests a batch may hold until the owner changes it with set_max_batch_size
+pub const DEFAULT_MAX_BATCH_SIZE: u32 = 100;
+
+// Nanoseconds a publish can be undone for until the owner changes it with set_undo_window, one hour
+pub const DEFAULT_UNDO_WINDOW_NS: u64 = 3_600_000_000_000;
+
+// Upper bound on the number of characters in a CIDv1, leaving room for a sha2-512 multihash
+pub const MAX_CID_LENGTH: usize = 128;
+
+// Prefix of the message an author's registered key signs for a manifest, so the signature cannot be replayed elsewhere
+pub const MANIFEST_SIGNATURE_DOMAIN: &[u8] = b"attestation-registry:manifest:";
+
+// Alphabets used by the multibase encodings accepted for cids
+const BASE58_ALPHABET: &str = "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+const BASE32_ALPHABET: &str = "abcdefghijklmnopqrstuvwxyz234567";
+
+// Bytes NEAR charges for every storage record on top of its key and value
+const STORAGE_RECORD_OVERHEAD: u64 = 40;
+
+// Upper bound on the number of bytes in a NEAR account id
+const MAX_ACCOUNT_ID_LENGTH: u64 = 64;
 
 #[near_bindgen]
 #[derive(BorshDeserialize, BorshSerialize)]
@@ -53,443 +254,6827 @@ pub struct Contract {
     pub attestations: LookupMap<AccountId, LookupMap<Namespace, Attestations>>,
     pub compiled_types: LookupMap<Namespace, Types>,
     pub type_list: UnorderedMap<String, u8>,
+    // The content type an author falls back to when publishing without one
+    pub default_content_types: LookupMap<AccountId, String>,
+    // Packages whose author has changed the self-attestation policy, absent entries allow it
+    pub allow_self_attestation: LookupMap<Namespace, bool>,
+    // Hex encoded merkle root over the manifests of each package, see get_package_root
+    pub package_roots: LookupMap<Namespace, String>,
+    // Accounts other than the author that may publish under the author's namespace
+    pub publish_allowlists: LookupMap<AccountId, Vec<AccountId>>,
+    // Pending liveness nonces an attestor must sign for a package namespace
+    pub challenges: LookupMap<(AccountId, Namespace), Vec<u8>>,
+    // Names of every package an account has published, kept alongside the non-iterable Releases map
+    pub package_names: LookupMap<AccountId, Vector<PackageName>>,
+    // Packages that opted into version validation, absent entries are freeform
+    pub versioning_schemes: LookupMap<Namespace, VersioningScheme>,
+    // The account allowed to perform administrative operations
+    pub owner_id: AccountId,
+    // Distinct accounts that have attested to each package, in order of their first attestation
+    pub package_attestors: LookupMap<Namespace, Vector<AccountId>>,
+    // Contracts the owner has vetted to publish under their own account with is_contract
+    pub contract_publishers: LookupSet<AccountId>,
+    // Running counts of packages and of manifests across every author, see total_packages and total_manifests
+    pub total_packages: u64,
+    pub total_manifests: u64,
+    // Every account that has a package under each name, see find_authors
+    pub package_authors: LookupMap<PackageName, Vec<AccountId>>,
+    // Every manifest a cid is published under in publish order, a cid may be reused across versions and packages
+    pub cid_origins: LookupMap<String, Vec<CidOrigin>>,
+    // Append-only list of every (author, package name) pair ever published, paged over by export_packages
+    pub package_index: Vector<(AccountId, PackageName)>,
+    // The pairs already in package_index, so republishing a removed package does not list it twice
+    pub indexed_packages: LookupSet<(AccountId, PackageName)>,
+    // Keys contract authors registered to sign the cid of every manifest published under them with is_contract
+    pub author_keys: LookupMap<AccountId, PublicKey>,
+    // Running count of the attestations made against every package of each author
+    pub author_attestations: LookupMap<AccountId, u64>,
+    // Channels such as latest or beta pointing at a version of each package namespace, iterable per package
+    pub tags: LookupMap<Namespace, UnorderedMap<String, String>>,
+    // Cids the owner flagged as malicious mapped to the reason, they cannot be published or updated to
+    pub blocked_cids: LookupMap<String, String>,
+    // The (namespace, attestor) pairs already in package_attestors, so indexing an attestor does not scan the list
+    pub indexed_attestors: LookupSet<(Namespace, AccountId)>,
+    // Transfers an author has offered for one of their packages mapped to the account that may accept them
+    pub package_transfers: LookupMap<(AccountId, PackageName), AccountId>,
+    // Every node of the merkle tree over the manifests of each package, so publishing rehashes a single path
+    pub package_trees: LookupMap<Namespace, MerkleTree>,
+    // Settings and limits set by the owner, see Config
+    pub config: Config,
 }
 
+// Default is kept for unit tests and makes the contract account its own owner, deployments should call new instead
 impl Default for Contract {
     fn default() -> Self {
         Self {
-            packages: LookupMap::new(PrefixKeys::Package),
-            attestations: LookupMap::new(PrefixKeys::Attestation),
+            packages: LookupMap::new(PrefixKeys::PackagesV2),
+            attestations: LookupMap::new(PrefixKeys::AttestationsV2),
             compiled_types: LookupMap::new(PrefixKeys::Types),
-            type_list: UnorderedMap::new(PrefixKeys::TypeList)
+            type_list: UnorderedMap::new(PrefixKeys::TypeList),
+            default_content_types: LookupMap::new(PrefixKeys::DefaultContentType),
+            allow_self_attestation: LookupMap::new(PrefixKeys::SelfAttestation),
+            package_roots: LookupMap::new(PrefixKeys::PackageRoot),
+            publish_allowlists: LookupMap::new(PrefixKeys::PublishAllowlist),
+            challenges: LookupMap::new(PrefixKeys::Challenge),
+            package_names: LookupMap::new(PrefixKeys::PackageNames),
+            versioning_schemes: LookupMap::new(PrefixKeys::VersioningScheme),
+            owner_id: near_sdk::env::current_account_id(),
+            package_attestors: LookupMap::new(PrefixKeys::PackageAttestors),
+            contract_publishers: LookupSet::new(PrefixKeys::ContractPublishers),
+            total_packages: 0,
+            total_manifests: 0,
+            package_authors: LookupMap::new(PrefixKeys::PackageAuthors),
+            cid_origins: LookupMap::new(PrefixKeys::CidOrigins),
+            package_index: Vector::new(PrefixKeys::PackageIndex),
+            indexed_packages: LookupSet::new(PrefixKeys::IndexedPackages),
+            author_keys: LookupMap::new(PrefixKeys::AuthorKeys),
+            author_attestations: LookupMap::new(PrefixKeys::AuthorAttestations),
+            tags: LookupMap::new(PrefixKeys::Tags),
+            blocked_cids: LookupMap::new(PrefixKeys::BlockedCids),
+            indexed_attestors: LookupSet::new(PrefixKeys::IndexedAttestors),
+            package_transfers: LookupMap::new(PrefixKeys::PackageTransfers),
+            package_trees: LookupMap::new(PrefixKeys::PackageTrees),
+            config: Config {
+                max_batch_size: DEFAULT_MAX_BATCH_SIZE,
+                paused: false,
+                max_versions_per_package: 0,
+                self_attestation_allowed: false,
+                blocked_cids: 0,
+                undo_window_ns: DEFAULT_UNDO_WINDOW_NS,
+                log_level: LogLevel::Events
+            }
         }
     }
 }
 
 #[near_bindgen]
 impl Contract {
-    /*
-        pub fn get_packages(&self, account_id: String) -> Vec<String> {
-            let id: AccountId = account_id.parse().unwrap();
-            return self.packages.get(&id).unwrap().iter().map(|p| p.name.clone()).collect();
-        }
-    */
+    // Initialize the registry with an explicit owner for administrative operations
+    // Private so nobody else can initialize a freshly deployed contract with their own owner before the deployer does
+    #[private]
+    #[init]
+    pub fn new(owner_id: AccountId) -> Self {
+        let mut contract = Self::default();
+        contract.owner_id = owner_id;
+        return contract;
+    }
 
     /* Helper Functions */
+
+    fn assert_owner(&self) {
+        require!(near_sdk::env::predecessor_account_id() == self.owner_id, "Only the owner can call this method");
+    }
+
+    fn assert_not_paused(&self) -> Result<(), RegistryError> {
+        return ensure(!self.config.paused, RegistryError::ContractPaused);
+    }
+
+    // Free-text progress logs are only written at the Verbose log level
+    fn log(&self, message: &str) {
+        if self.config.log_level == LogLevel::Verbose {
+            log_str(message);
+        }
+    }
+
+    // NEP-297 events are written at every log level except Off
+    fn emit(&self, event: RegistryEvent) {
+        if self.config.log_level != LogLevel::Off {
+            event.emit();
+        }
+    }
+
+    // Nested collections get their own storage prefix made of a tag followed by sha256(account_id)
+    // so that maps belonging to different accounts never share keys
+    fn nested_prefix(tag: PrefixKeys, account_id: &AccountId) -> Vec<u8> {
+        let mut prefix = near_sdk::borsh::to_vec(&tag).unwrap();
+        prefix.extend(near_sdk::env::sha256(account_id.as_bytes()));
+        return prefix;
+    }
+
+    // Accepts CIDv0 (46 character base58btc starting with Qm) and CIDv1 (lowercase base32 multibase starting with b)
+    fn validate_cid(cid: &str) -> bool {
+        if cid.starts_with("Qm") {
+            return cid.len() == 46 && cid.chars().all(|c| BASE58_ALPHABET.contains(c));
+        }
+
+        if let Some(body) = cid.strip_prefix('b') {
+            // A CIDv1 with a sha2-256 multihash is the shortest common form at 59 characters
+            return cid.len() >= 59
+                && cid.len() <= MAX_CID_LENGTH
+                && body.chars().all(|c| BASE32_ALPHABET.contains(c));
+        }
+
+        return false;
+    }
+
+    // Package names are 1 to 64 characters of lowercase letters, digits, dots, underscores and dashes
+    fn validate_package_name(package_name: &str) -> bool {
+        return !package_name.is_empty()
+            && package_name.len() <= MAX_PACKAGE_NAME_LENGTH
+            && package_name.bytes().all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b"._-".contains(&b));
+    }
+
     fn generate_key(author: AccountId, package_name: String) -> Namespace {
         let key = author.as_str().to_owned() + package_name.as_str();
         return near_sdk::env::sha256(key.as_bytes());
     }
 
-    fn safe_package_retrieval(&self, account_id: AccountId) -> Releases {
-        require!(self.packages.contains_key(&account_id), "No packages found for account_id");
-        return self.packages.get(&account_id).unwrap();
+    fn get_releases(&self, account_id: &AccountId) -> Result<Releases, RegistryError> {
+        return self.packages.get(account_id).ok_or(RegistryError::NoPackagesFound);
     }
 
-    fn safe_attestation_retrieval(
-        &mut self,
+    // The versions of a package for view methods, None when the author or package is missing
+    fn find_versions(&self, account_id: &AccountId, package_name: &PackageName) -> Option<Vec<Manifest>> {
+        return self.packages.get(account_id).and_then(|manifests| manifests.get(package_name));
+    }
+
+    fn get_attestation_bucket(
+        &self,
         manifests: Releases,
         attestor: AccountId,
         author: AccountId,
         package_name: String
-    ) -> Attestations {
-        require!(manifests.contains_key(&package_name), "Package name not found for given author");
-        require!(self.attestations.contains_key(&attestor), "Attestor not found");
+    ) -> Result<Attestations, RegistryError> {
+        ensure(manifests.contains_key(&package_name), RegistryError::PackageNotFound)?;
+        let at = self.attestations.get(&attestor).ok_or(RegistryError::AttestorNotFound)?;
 
-        let at = self.attestations.get(&attestor).unwrap();
+        // An attestor known from other packages may still never have attested to this one
         let hash = Self::generate_key(author, package_name);
-        return at.get(&hash).unwrap();
+        return at.get(&hash).ok_or(RegistryError::AttestorNotFound);
     }
 
-    /* Public Methods */
+    // Non-reverting form of get_attestation_bucket for view methods, None when the package or attestor is missing
+    fn find_attestations(&self, attestor: AccountId, author: AccountId, package_name: String) -> Option<Attestations> {
+        let manifests = self.get_releases(&author).ok()?;
+        return self.get_attestation_bucket(manifests, attestor, author, package_name).ok();
+    }
 
-    // Create a manifest resource for a package
-    pub fn create_manifest(
-        &mut self,
-        // A string representing the name of a particular package
-        package_name: String,
-        // The version string that can represent either semantic versioning or any other format
-        version: String,
-        // Specifies the type of content once resolved via the content id
-        content_type: String,
-        // The IPFS content id that contains the package manifest
-        cid: String,
-        // A list of named types in the package
-        mut types: Vec<String>,
-        // If a contract is calling this function the reference key can be the contract account if true or the signers account when false
-        is_contract: bool,
-    ) {
-        let manifest = Manifest {
-            version,
-            content_type,
-            cid,
-            types: types.clone()
+    fn verify_signature(pubkey: &PublicKey, signature: &[u8], message: &[u8]) -> bool {
+        if pubkey.curve_type() != CurveType::ED25519 {
+            return false;
+        }
+
+        // The first byte of a public key encodes its curve type
+        let key: Result<[u8; 32], _> = pubkey.as_bytes()[1..].try_into();
+        let sig: Result<[u8; 64], _> = signature.try_into();
+
+        return match (sig, key) {
+            (Ok(sig), Ok(key)) => near_sdk::env::ed25519_verify(&sig, message, &key),
+            _ => false,
         };
+    }
 
-        let mut author = near_sdk::env::signer_account_id();
+    // Every package enters and leaves the registry through these two, so they also maintain total_packages
+    fn index_package_name(&mut self, author: &AccountId, package_name: &PackageName) {
+        let mut names = self.package_names.get(author).unwrap_or_else(|| {
+            Vector::new(Self::nested_prefix(PrefixKeys::PackageNames, author))
+        });
 
-        if is_contract {
-            author = near_sdk::env::predecessor_account_id();
-            log_str(&format!("Using contract as the author"));
-        }
+        names.push(package_name);
+        self.package_names.insert(author, &names);
+        self.total_packages += 1;
 
-        if !self.packages.contains_key(&author) {
-            self.packages.insert(
-                &author,
-                &LookupMap::new(PrefixKeys::Manifest)
-            );
-            log_str(&format!("Creating storage..."));
-        }
+        let mut authors = self.package_authors.get(package_name).unwrap_or_default();
+        authors.push(author.clone());
+        self.package_authors.insert(package_name, &authors);
 
-        if !self.packages.get(&author).unwrap().contains_key(&package_name) {
-            let mut manifests = self.packages.get(&author).unwrap();
-            manifests.insert(&package_name, &mut Vec::new());
+        let pair = (author.clone(), package_name.clone());
+        if self.indexed_packages.insert(&pair) {
+            self.package_index.push(&pair);
         }
+    }
 
-        log_str(&format!("Writing manifest for {package_name}..."));
-        let mut manifests = self.packages.get(&author).unwrap();
-        let mut versions = manifests.get(&package_name)
-            .unwrap();
-
-        versions.push(manifest);
-        manifests.insert(&package_name, &versions);
+    fn unindex_package_name(&mut self, author: &AccountId, package_name: &PackageName) {
+        if let Some(mut names) = self.package_names.get(author) {
+            if let Some(index) = names.iter().position(|name| name == *package_name) {
+                // Shift the later names down rather than swap_remove, so get_packages and its pages keep publish order
+                for later in index as u64 + 1..names.len() {
+                    let name = names.get(later).unwrap();
+                    names.replace(later - 1, &name);
+                }
 
-        let namespace = Self::generate_key(author, package_name);
+                names.pop();
+                self.package_names.insert(author, &names);
+                self.total_packages -= 1;
+            }
+        }
 
-        if !self.compiled_types.contains_key(&namespace) {
-            self.compiled_types.insert(&namespace, &types);
+        if let Some(mut authors) = self.package_authors.get(package_name) {
+            authors.retain(|account| account != author);
+            match authors.is_empty() {
+                true => self.package_authors.remove(package_name),
+                false => self.package_authors.insert(package_name, &authors),
+            };
         }
+    }
 
-        else {
-            let mut compiled_types = self.compiled_types.get(&namespace).unwrap();
-            compiled_types.append(&mut types)
+    fn index_cids(&mut self, author: &AccountId, package_name: &PackageName, versions: &[Manifest]) {
+        for manifest in versions {
+            let mut origins = self.cid_origins.get(&manifest.cid).unwrap_or_default();
+            origins.push((author.clone(), package_name.clone(), manifest.version.clone()));
+            self.cid_origins.insert(&manifest.cid, &origins);
         }
+    }
 
+    fn unindex_cids(&mut self, author: &AccountId, package_name: &PackageName, versions: &[Manifest]) {
+        for manifest in versions {
+            if let Some(mut origins) = self.cid_origins.get(&manifest.cid) {
+                origins.retain(|(a, p, v)| a != author || p != package_name || *v != manifest.version);
+                match origins.is_empty() {
+                    true => self.cid_origins.remove(&manifest.cid),
+                    false => self.cid_origins.insert(&manifest.cid, &origins),
+                };
+            }
+        }
     }
 
-    // Retrieves the last manifest for a particular package
-    pub fn get_latest_manifest(
-        &self,
-        // An account ID of the author who published the manifest
-        account_id: AccountId,
-        // A string representing the name of a particular package
-        package_name: String
-    ) -> String {
-        let manifests = self.safe_package_retrieval(account_id);
-        require!(manifests.contains_key(&package_name), "Package name not found for given account_id");
+    // Charges the caller for the storage written since initial_storage out of the attached deposit
+    // and refunds whatever is left over, an error reverts the writes once returned from the method
+    fn charge_storage(initial_storage: u64) -> Result<(), RegistryError> {
+        let bytes = near_sdk::env::storage_usage().saturating_sub(initial_storage);
+        let required = near_sdk::env::storage_byte_cost().as_yoctonear() * bytes as u128;
+        let attached = near_sdk::env::attached_deposit().as_yoctonear();
 
-        return manifests.get(&package_name)
-            .unwrap()
-            .last()
-            .unwrap()
-            .cid.clone();
-    }
+        ensure(attached >= required, RegistryError::InsufficientDeposit(required))?;
 
-    // Get a single manifest file given a version and package name
-    // It will return a string saying "None" if no manifest was found
-    pub fn get_manifest(
-        &self,
-        // An account ID of the author who published the manifest
-        account_id: AccountId,
-        // A string representing the name of a particular package
-        package_name: String,
-        // The version string that is used to retreive the manifest
-        version: String
-    ) -> String {
-        let manifests = self.safe_package_retrieval(account_id);
-        require!(manifests.contains_key(&package_name), "Package name not found for given account_id");
-
-        let versions = manifests.get(&package_name).unwrap();
-        for v in versions {
-            if v.version == version {
-                return v.cid
-            }
+        let refund = attached - required;
+        if refund > 0 {
+            Promise::new(near_sdk::env::predecessor_account_id()).transfer(NearToken::from_yoctonear(refund));
         }
 
-        return "None".to_string();
+        return Ok(());
     }
 
-    // Update a particular manifest file given the package name and version
-    pub fn update_manifest(
-        &mut self,
-        // A string representing the name of a particular package
-        package_name: String,
-        // The version string that is used to retreive the manifest
-        version: String,
-        // The new content type if changed
-        content_type: String,
-        // The IPFS content to replace the existing one
-        cid: String
-    ) {
-        let mut manifests = self.safe_package_retrieval(near_sdk::env::signer_account_id());
-        let versions = manifests.get(&package_name).unwrap().clone();
-
-        log_str(&format!("Updating existing manifest for {package_name} and {version}..."));
+    fn index_attestor(&mut self, namespace: &Namespace, attestor: &AccountId) {
+        if !self.indexed_attestors.insert(&(namespace.clone(), attestor.clone())) {
+            return;
+        }
 
-        let v = versions.clone().into_iter().map(|mut m| {
-                if m.version == version {
-                    m.cid = cid.clone();
-                    m.content_type = content_type.clone();
-                }
+        let mut attestors = self.package_attestors.get(namespace).unwrap_or_else(|| {
+            // The namespace is already a sha256 hash so it is used directly after the tag
+            let mut prefix = near_sdk::borsh::to_vec(&PrefixKeys::PackageAttestors).unwrap();
+            prefix.extend(namespace);
+            Vector::new(prefix)
+        });
 
-                return m;
-            }
-        ).collect::<Vec<Manifest>>();
+        attestors.push(attestor);
+        self.package_attestors.insert(namespace, &attestors);
+    }
 
-        manifests.insert(&package_name, &v);
+    // The tags of a package namespace, created empty on first use
+    fn package_tags(&self, namespace: &Namespace) -> UnorderedMap<String, String> {
+        return self.tags.get(namespace).unwrap_or_else(|| {
+            // The namespace is already a sha256 hash so it is used directly after the tag
+            let mut prefix = near_sdk::borsh::to_vec(&PrefixKeys::PackageTags).unwrap();
+            prefix.extend(namespace);
+            UnorderedMap::new(prefix)
+        });
     }
 
-    // Add an attestation for a package that exists inside of the registry
-    pub fn create_attestation(
-        &mut self,
-        // A string representing the name of a particular package
-        package_name: String,
-        // An account ID of the author who published the manifest
-        author: AccountId,
-        // An IPFS content ID that contains the attestation data
-        cid: String
-    ) {
-        let manifests = self.safe_package_retrieval(author.clone());
-        let attest = Attestation {
-            pubkey: near_sdk::env::signer_account_pk(),
-            cid
+    // The last published version that is not yanked, or the last version if every one is yanked
+    fn latest_unyanked(mut versions: Vec<Manifest>) -> Option<Manifest> {
+        return match versions.iter().rposition(|m| !m.yanked) {
+            Some(index) => Some(versions.swap_remove(index)),
+            None => versions.pop(),
         };
+    }
 
-        let hash = Self::generate_key(author.clone(), package_name.clone());
+    // Download counts change on every fetch rather than with the published content, so leaves hash them as zero
+    fn manifest_leaf(manifest: &Manifest) -> Vec<u8> {
+        let content = Manifest { downloads: 0, ..manifest.clone() };
+        return merkle::hash_leaf(&near_sdk::borsh::to_vec(&content).unwrap());
+    }
 
-        if !self.attestations.contains_key(&near_sdk::env::signer_account_id()) {
-            self.attestations.insert(
-                &near_sdk::env::signer_account_id(),
-                &LookupMap::new(PrefixKeys::Attestation)
-            );
+    // The merkle tree of a package namespace, created empty on first use
+    fn package_tree(&self, namespace: &Namespace) -> MerkleTree {
+        return self.package_trees.get(namespace).unwrap_or_else(|| {
+            // The namespace is already a sha256 hash so it is used directly after the tag
+            let mut prefix = near_sdk::borsh::to_vec(&PrefixKeys::PackageTreeNodes).unwrap();
+            prefix.extend(namespace);
+            MerkleTree::new(prefix)
+        });
+    }
 
-            log_str(&format!("Creating attestation storage..."));
-            let mut at = self.attestations.get(&near_sdk::env::signer_account_id()).unwrap();
-            at.insert(&hash, &mut Vec::new());
-        }
+    // Stores a package's tree after a change together with its hex encoded root
+    fn save_package_tree(&mut self, namespace: &Namespace, tree: &MerkleTree) {
+        self.package_trees.insert(namespace, tree);
+        self.package_roots.insert(namespace, &merkle::to_hex(&tree.root()));
+    }
 
-        let mut user_atts = self.safe_attestation_retrieval(
-            manifests,
-            near_sdk::env::signer_account_id(),
-            author,
-            package_name
-        );
+    // Appends newly published versions to the package root, rehashing only their paths
+    fn append_package_leaves(&mut self, namespace: &Namespace, manifests: &[Manifest]) {
+        let mut tree = self.package_tree(namespace);
+        for manifest in manifests {
+            tree.push(Self::manifest_leaf(manifest));
+        }
 
-        user_atts.push(attest);
-        let mut at = self.attestations.get(&near_sdk::env::signer_account_id()).unwrap();
-        at.insert(&hash, &user_atts);
+        self.save_package_tree(namespace, &tree);
     }
 
-    // Retrieve all of the attestations for a given package and signer account ID
-    // Returns a list of attestation objects if they exist in the registry, otherwise reverts
-    pub fn get_attestations(
-        &mut self,
-        // The author of the attestation
-        attestor: AccountId,
-        // The package name that the attestor has made a claim against
-        package_name: String,
-        // The author for a particular package
-        author: AccountId
-    ) -> Attestations {
-        let manifests = self.safe_package_retrieval(author.clone());
+    // Rehashes the path of a version changed in place
+    fn update_package_leaf(&mut self, namespace: &Namespace, index: usize, manifest: &Manifest) {
+        let mut tree = self.package_tree(namespace);
+        tree.set_leaf(index as u64, Self::manifest_leaf(manifest));
+        self.save_package_tree(namespace, &tree);
+    }
 
-        return self.safe_attestation_retrieval(
-            manifests,
-            attestor,
-            author,
-            package_name
-        );
+    // Drops the last version from the package root, rehashing only the new last version's path
+    fn pop_package_leaf(&mut self, namespace: &Namespace) {
+        let mut tree = self.package_tree(namespace);
+        tree.pop();
+        self.save_package_tree(namespace, &tree);
     }
 
+    // Removes every node of a package's tree and its root
+    fn clear_package_tree(&mut self, namespace: &Namespace) {
+        if let Some(mut tree) = self.package_trees.remove(namespace) {
+            tree.clear();
+        }
 
-    // Retrieve a single attestation at a particular index
-    // Returns an attestation object if an index is known in advance
-    pub fn get_attestation(
-        &mut self,
-        // The author of the attestation
-        attestor: AccountId,
-        // The package name that the attestor has made a claim against
-        package_name: String,
-        // The author for a particular package
-        author: AccountId,
-        // An index containing an attestation object
-        index: usize
-    ) -> Attestation {
-        let at = self.get_attestations(attestor, package_name, author);
+        self.package_roots.remove(namespace);
+    }
 
-        return at[index].clone();
+    // Drops a package from the author's releases along with everything stored under its namespace,
+    // shared by remove_package and delete_manifest removing the last version
+    fn clear_package(&mut self, author: &AccountId, manifests: &mut Releases, package_name: &PackageName) {
+        manifests.remove(package_name);
+        self.unindex_package_name(author, package_name);
+        self.package_transfers.remove(&(author.clone(), package_name.clone()));
+
+        let namespace = Self::generate_key(author.clone(), package_name.clone());
+        self.clear_package_tree(&namespace);
+        self.compiled_types.remove(&namespace);
+        self.versioning_schemes.remove(&namespace);
+        self.clear_tags(&namespace);
     }
 
-    pub fn attest_to_type(
-        &mut self,
-        // An account ID of the author who published the manifest
-        author: AccountId,
-        // Name of a type in a package
-        type_name: String,
-    ) {
-        if self.attestations.contains_key(&author) {
-            let count: u8 = match self.type_list.get(&type_name) {
-                Some(v) => v + 1u8,
-                None => 1u8,
-            };
+    // Drops every attestation recorded against a package namespace along with its attestor index and challenges
+    fn clear_attestations(&mut self, author: &AccountId, namespace: &Namespace) {
+        if let Some(mut attestors) = self.package_attestors.remove(namespace) {
+            let mut removed = 0;
+            for attestor in attestors.iter() {
+                if let Some(mut at) = self.attestations.get(&attestor) {
+                    removed += at.remove(namespace).map_or(0, |atts| atts.len() as u64);
+                }
+                self.indexed_attestors.remove(&(namespace.clone(), attestor.clone()));
+                self.challenges.remove(&(attestor, namespace.clone()));
+            }
+            attestors.clear();
 
-            self.type_list.insert(&type_name, &count);
+            let total = self.author_attestations.get(author).unwrap_or(0);
+            self.author_attestations.insert(author, &total.saturating_sub(removed));
         }
+
+        self.allow_self_attestation.remove(namespace);
     }
-}
 
-#[cfg(all(test, not(target_arch = "wasm32")))]
-mod tests {
-    use super::*;
-    use near_sdk::test_utils::VMContextBuilder;
-    use near_sdk::{testing_env, VMContext};
+    // Drops every tag of a package namespace so a package republished under it starts without stale tags
+    fn clear_tags(&mut self, namespace: &Namespace) {
+        if let Some(mut tags) = self.tags.remove(namespace) {
+            tags.clear();
+        }
+    }
 
-    fn get_context(is_view: bool) -> VMContext {
-        VMContextBuilder::new()
-            .signer_account_id("bob_near".parse().unwrap())
-            .is_view(is_view)
-            .build()
+    // Rebuilds the package's tree from every version, for changes that drop or reorder versions before the last one
+    fn refresh_package_root(&mut self, namespace: &Namespace, versions: &[Manifest]) {
+        self.clear_package_tree(namespace);
+        self.append_package_leaves(namespace, versions);
     }
 
-    #[test]
-    fn set_package_manifest() {
-        let context = get_context(false);
-        testing_env!(context.clone());
-        let cid = "QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB".to_string();
+    // The author is the signer, or the calling contract when is_contract is set, in which case
+    // the contract must be registered by the owner and the signer must be on its publish allowlist
+    fn resolve_author(&self, is_contract: bool) -> Result<AccountId, RegistryError> {
+        let mut author = near_sdk::env::signer_account_id();
+
+        if is_contract {
+            author = near_sdk::env::predecessor_account_id();
+            ensure(self.contract_publishers.contains(&author), RegistryError::UnregisteredContract)?;
+            self.log(&format!("Using contract as the author"));
+        }
+
+        let signer = near_sdk::env::signer_account_id();
+        if author != signer {
+            let allowlist = self.publish_allowlists.get(&author).unwrap_or_default();
+            ensure(allowlist.contains(&signer), RegistryError::NotAllowedToPublish)?;
+        }
+
+        return Ok(author);
+    }
+
+    // Contracts act as the predecessor, which must differ from the signer so an account calling directly
+    // cannot record keyless attestations under its own name
+    fn resolve_attestor(is_contract: bool) -> Result<AccountId, RegistryError> {
+        let signer = near_sdk::env::signer_account_id();
+        if !is_contract {
+            return Ok(signer);
+        }
+
+        let predecessor = near_sdk::env::predecessor_account_id();
+        ensure(predecessor != signer, RegistryError::NotCalledByContract)?;
+        return Ok(predecessor);
+    }
+
+    // Authors that registered a key must sign every manifest published under them with is_contract,
+    // so a compromised contract relaying calls cannot publish without the author's consent
+    // The message binds the author, package name and version to the cid, so a signature cannot be replayed for another release
+    fn ensure_author_signature(
+        &self,
+        author: &AccountId,
+        package_name: &PackageName,
+        version: &str,
+        cid: &str,
+        signature: &Option<Vec<u8>>
+    ) -> Result<(), RegistryError> {
+        if let Some(key) = self.author_keys.get(author) {
+            let signature = signature.as_ref().ok_or(RegistryError::MissingManifestSignature)?;
+            let mut message = MANIFEST_SIGNATURE_DOMAIN.to_vec();
+            message.extend(near_sdk::borsh::to_vec(&(author, package_name, version, cid)).unwrap());
+            ensure(Self::verify_signature(&key, signature, &message), RegistryError::InvalidManifestSignature)?;
+        }
+
+        return Ok(());
+    }
+
+    fn ensure_cid_not_blocked(&self, cid: &str) -> Result<(), RegistryError> {
+        return match self.blocked_cids.get(&cid.to_string()) {
+            Some(reason) => Err(RegistryError::BlockedCid(reason)),
+            None => Ok(()),
+        };
+    }
+
+    // Validates the fields of a manifest about to be published, filling in the author's default content type
+    fn build_manifest(
+        &self,
+        author: &AccountId,
+        package_name: &PackageName,
+        version: String,
+        mut content_type: String,
+        cid: String
+    ) -> Result<Manifest, RegistryError> {
+        if content_type.is_empty() {
+            content_type = self.default_content_types.get(author).ok_or(RegistryError::MissingContentType)?;
+        }
+
+        let content_type = ContentType::parse(&content_type).ok_or(RegistryError::InvalidContentType)?.to_string();
+        ensure(Self::validate_cid(&cid), RegistryError::InvalidCid)?;
+        self.ensure_cid_not_blocked(&cid)?;
+
+        let scheme = self.versioning_schemes.get(&Self::generate_key(author.clone(), package_name.clone()));
+        if scheme == Some(VersioningScheme::Semver) {
+            ensure(Version::parse(&version).is_some(), RegistryError::InvalidVersion)?;
+        }
+
+        return Ok(Manifest {
+            version,
+            content_type,
+            cid,
+            types: Vec::new(),
+            encryption: None,
+            created_at: near_sdk::env::block_timestamp(),
+            updated_at: 0,
+            yanked: false,
+            downloads: 0,
+            dependencies: Vec::new()
+        });
+    }
+
+    // Creates the author's release storage and the package entry on first publish
+    fn ensure_package_storage(&mut self, author: &AccountId, package_name: &PackageName) {
+        if !self.packages.contains_key(author) {
+            self.packages.insert(
+                author,
+                &LookupMap::new(Self::nested_prefix(PrefixKeys::ManifestV2, author))
+            );
+            self.log(&format!("Creating storage..."));
+        }
+
+        if !self.packages.get(author).unwrap().contains_key(package_name) {
+            let mut manifests = self.packages.get(author).unwrap();
+            manifests.insert(package_name, &Vec::new());
+            self.index_package_name(author, package_name);
+        }
+    }
+
+    /* Public Methods */
+
+    // Retrieves the account allowed to perform administrative operations
+    pub fn get_owner(&self) -> AccountId {
+        return self.owner_id.clone();
+    }
+
+    // Hand administrative control of the registry to another account, only callable by the owner
+    pub fn set_owner(
+        &mut self,
+        // The account that becomes the new owner
+        owner_id: AccountId
+    ) {
+        self.assert_owner();
+        self.log(&format!("Transferring ownership to {owner_id}..."));
+        self.owner_id = owner_id;
+    }
+
+    // Freeze every mutating entrypoint for incident response, only callable by the owner
+    pub fn pause(&mut self) {
+        self.assert_owner();
+        self.log(&format!("Pausing registry..."));
+        self.config.paused = true;
+    }
+
+    // Resume writes after a pause, only callable by the owner
+    pub fn unpause(&mut self) {
+        self.assert_owner();
+        self.log(&format!("Unpausing registry..."));
+        self.config.paused = false;
+    }
+
+    // Every setting and limit the owner controls in one call, for clients discovering the registry's configuration
+    pub fn get_config(&self) -> Config {
+        return self.config.clone();
+    }
+
+    // Whether writes to the registry are currently frozen
+    pub fn is_paused(&self) -> bool {
+        return self.config.paused;
+    }
+
+    // Allow or forbid authors across the registry from attesting to their own packages, only callable by the owner
+    pub fn set_self_attestation_allowed(
+        &mut self,
+        // Whether authors may attest to their own packages
+        allowed: bool
+    ) {
+        self.assert_owner();
+        self.log(&format!("Setting self-attestation allowed to {allowed}..."));
+        self.config.self_attestation_allowed = allowed;
+    }
+
+    // Cap the number of versions a package may hold, zero removes the cap, only callable by the owner
+    // Packages already above the cap keep their versions but cannot publish new ones
+    pub fn set_max_versions_per_package(
+        &mut self,
+        // The maximum number of versions per package
+        max_versions: u32
+    ) {
+        self.assert_owner();
+        self.log(&format!("Setting max versions per package to {max_versions}..."));
+        self.config.max_versions_per_package = max_versions;
+    }
+
+    // The maximum number of versions a package may hold, zero if unlimited
+    pub fn get_max_versions_per_package(&self) -> u32 {
+        return self.config.max_versions_per_package;
+    }
+
+    // Cap the number of manifests a single batch_create_manifests call may publish, zero removes the cap,
+    // only callable by the owner
+    pub fn set_max_batch_size(
+        &mut self,
+        // The maximum number of manifests per batch
+        max_batch_size: u32
+    ) {
+        self.assert_owner();
+        self.log(&format!("Setting max batch size to {max_batch_size}..."));
+        self.config.max_batch_size = max_batch_size;
+    }
+
+    // Set how long after publishing an author may undo the publish, zero disables undo, only callable by the owner
+    pub fn set_undo_window(
+        &mut self,
+        // The grace window in nanoseconds
+        undo_window_ns: u64
+    ) {
+        self.assert_owner();
+        self.log(&format!("Setting undo window to {undo_window_ns} ns..."));
+        self.config.undo_window_ns = undo_window_ns;
+    }
+
+    // Choose which logs the registry writes, Events keeps NEP-297 events and drops free-text logs,
+    // only callable by the owner
+    pub fn set_log_level(
+        &mut self,
+        // The new log level
+        log_level: LogLevel
+    ) {
+        self.assert_owner();
+        self.log(&format!("Setting log level to {log_level:?}..."));
+        self.config.log_level = log_level;
+    }
+
+    fn ensure_version_capacity(&self, version_count: usize) -> Result<(), RegistryError> {
+        let max_versions = self.config.max_versions_per_package;
+        return ensure(
+            max_versions == 0 || version_count <= max_versions as usize,
+            RegistryError::VersionLimitReached
+        );
+    }
+
+    // Whether the registry lets authors attest to their own packages
+    pub fn is_self_attestation_allowed(&self) -> bool {
+        return self.config.self_attestation_allowed;
+    }
+
+    // Flag a cid as malicious so it can no longer be published or updated to, only callable by the owner
+    // Manifests already pointing at the cid are left in place, clients check is_cid_blocked before fetching
+    pub fn block_cid(
+        &mut self,
+        // The IPFS content id to block
+        cid: String,
+        // Why the cid is blocked, shown to publishers and clients
+        reason: String
+    ) {
+        self.assert_owner();
+        self.log(&format!("Blocking cid {cid}..."));
+        if self.blocked_cids.insert(&cid, &reason).is_none() {
+            self.config.blocked_cids += 1;
+        }
+    }
+
+    // Lift the block on a cid, only callable by the owner
+    pub fn unblock_cid(
+        &mut self,
+        // The IPFS content id to unblock
+        cid: String
+    ) {
+        self.assert_owner();
+        self.log(&format!("Unblocking cid {cid}..."));
+        if self.blocked_cids.remove(&cid).is_some() {
+            self.config.blocked_cids -= 1;
+        }
+    }
+
+    // The reason a cid is blocked, None if it is not blocked
+    pub fn is_cid_blocked(
+        &self,
+        // The IPFS content id to check
+        cid: String
+    ) -> Option<String> {
+        return self.blocked_cids.get(&cid);
+    }
+
+    // Allow a contract to publish under its own account with is_contract, only callable by the owner
+    pub fn register_contract_publisher(
+        &mut self,
+        // The contract account that forwards create_manifest calls
+        account_id: AccountId
+    ) {
+        self.assert_owner();
+        self.log(&format!("Registering contract publisher {account_id}..."));
+        self.contract_publishers.insert(&account_id);
+    }
+
+    // Revoke a contract's permission to publish with is_contract, only callable by the owner
+    pub fn unregister_contract_publisher(
+        &mut self,
+        // The contract account that forwards create_manifest calls
+        account_id: AccountId
+    ) {
+        self.assert_owner();
+        self.log(&format!("Unregistering contract publisher {account_id}..."));
+        self.contract_publishers.remove(&account_id);
+    }
+
+    // Whether a contract may publish under its own account with is_contract
+    pub fn is_contract_publisher(
+        &self,
+        // The contract account that forwards create_manifest calls
+        account_id: AccountId
+    ) -> bool {
+        return self.contract_publishers.contains(&account_id);
+    }
+
+    // Create a manifest resource for a package
+    // With is_contract the predecessor becomes the author, which would let any contract relaying a signer's call
+    // claim package names under its account, so the predecessor must be registered by the owner
+    #[payable]
+    #[handle_result]
+    pub fn create_manifest(
+        &mut self,
+        // A string representing the name of a particular package
+        package_name: String,
+        // The version string that can represent either semantic versioning or any other format
+        version: String,
+        // Specifies the type of content once resolved via the content id, an empty string uses the author's default
+        content_type: String,
+        // The IPFS content id that contains the package manifest
+        cid: String,
+        // A list of named types in the package
+        types: Vec<String>,
+        // The encryption scheme and key reference if the content is encrypted
+        encryption: Option<EncryptionInfo>,
+        // If a contract is calling this function the reference key can be the contract account if true or the signers account when false
+        is_contract: bool,
+        // An ed25519 signature over the release by the author's registered key, required with is_contract once a key is registered
+        signature: Option<Vec<u8>>,
+        // The author, package name and version requirement of every package this version depends on
+        dependencies: Vec<Dependency>,
+    ) -> Result<(), RegistryError> {
+        self.assert_not_paused()?;
+
+        let initial_storage = near_sdk::env::storage_usage();
+        self.write_manifest(
+            package_name,
+            version,
+            content_type,
+            cid,
+            types,
+            encryption,
+            is_contract,
+            signature,
+            dependencies
+        )?;
+        return Self::charge_storage(initial_storage);
+    }
+
+    // Validates and stores a new manifest without charging for storage, so callers writing more than one
+    // record charge the attached deposit once for everything they wrote
+    fn write_manifest(
+        &mut self,
+        package_name: String,
+        version: String,
+        content_type: String,
+        cid: String,
+        mut types: Vec<String>,
+        encryption: Option<EncryptionInfo>,
+        is_contract: bool,
+        signature: Option<Vec<u8>>,
+        dependencies: Vec<Dependency>
+    ) -> Result<(), RegistryError> {
+        ensure(Self::validate_package_name(&package_name), RegistryError::InvalidPackageName)?;
+
+        let author = self.resolve_author(is_contract)?;
+        if is_contract {
+            self.ensure_author_signature(&author, &package_name, &version, &cid, &signature)?;
+        }
+        let mut manifest = self.build_manifest(&author, &package_name, version, content_type, cid)?;
+
+        ensure(
+            encryption.as_ref().map_or(true, |e| !e.scheme.is_empty() && !e.key_ref.is_empty()),
+            RegistryError::InvalidEncryption
+        )?;
+        ensure(dependencies.len() <= MAX_DEPENDENCIES, RegistryError::TooManyDependencies)?;
+
+        manifest.types = types.clone();
+        manifest.encryption = encryption;
+        manifest.dependencies = dependencies;
+
+        let mut versions = self.packages.get(&author)
+            .and_then(|releases| releases.get(&package_name))
+            .unwrap_or_default();
+
+        // Existing versions can only be changed intentionally through update_manifest
+        ensure(
+            !versions.iter().any(|m| m.version == manifest.version),
+            RegistryError::VersionExists
+        )?;
+        self.ensure_version_capacity(versions.len() + 1)?;
+
+        self.ensure_package_storage(&author, &package_name);
+
+        self.log(&format!("Writing manifest for {package_name}..."));
+        let mut manifests = self.packages.get(&author).unwrap();
+
+        let event = ManifestEventData {
+            author: author.clone(),
+            package_name: package_name.clone(),
+            version: manifest.version.clone(),
+            cid: manifest.cid.clone()
+        };
+
+        self.index_cids(&author, &package_name, std::slice::from_ref(&manifest));
+        versions.push(manifest);
+        manifests.insert(&package_name, &versions);
+        self.total_manifests += 1;
+
+        let namespace = Self::generate_key(author, package_name);
+        self.append_package_leaves(&namespace, &versions[versions.len() - 1..]);
+        self.emit(RegistryEvent::ManifestCreated(vec![event]));
+
+        if !self.compiled_types.contains_key(&namespace) {
+            self.compiled_types.insert(&namespace, &types);
+        }
+
+        else {
+            let mut compiled_types = self.compiled_types.get(&namespace).unwrap();
+            compiled_types.append(&mut types)
+        }
+
+        return Ok(());
+    }
+
+    // Publish several versions of a package at once, every entry is validated before any is written
+    // so a single invalid or duplicate version reverts the whole batch
+    #[payable]
+    #[handle_result]
+    pub fn batch_create_manifests(
+        &mut self,
+        // A string representing the name of a particular package
+        package_name: String,
+        // The version, content type and IPFS content id of every manifest to publish, in publish order
+        manifests: Vec<(String, String, String)>,
+        // If a contract is calling this function the reference key can be the contract account if true or the signers account when false
+        is_contract: bool
+    ) -> Result<(), RegistryError> {
+        self.assert_not_paused()?;
+
+        let initial_storage = near_sdk::env::storage_usage();
+        ensure(!manifests.is_empty(), RegistryError::EmptyBatch)?;
+        ensure(
+            self.config.max_batch_size == 0 || manifests.len() <= self.config.max_batch_size as usize,
+            RegistryError::BatchTooLarge(self.config.max_batch_size)
+        )?;
+        ensure(Self::validate_package_name(&package_name), RegistryError::InvalidPackageName)?;
+        let author = self.resolve_author(is_contract)?;
+        // Batches carry no signatures, so authors with a registered key publish through create_manifest
+        ensure(!is_contract || !self.author_keys.contains_key(&author), RegistryError::MissingManifestSignature)?;
+
+        let mut versions = self.packages.get(&author)
+            .and_then(|releases| releases.get(&package_name))
+            .unwrap_or_default();
+        let published = versions.len();
+
+        let mut events = Vec::new();
+        for (version, content_type, cid) in manifests {
+            let manifest = self.build_manifest(&author, &package_name, version, content_type, cid)?;
+            ensure(
+                !versions.iter().any(|m| m.version == manifest.version),
+                RegistryError::VersionExists
+            )?;
+
+            events.push(ManifestEventData {
+                author: author.clone(),
+                package_name: package_name.clone(),
+                version: manifest.version.clone(),
+                cid: manifest.cid.clone()
+            });
+
+            versions.push(manifest);
+        }
+
+        self.ensure_version_capacity(versions.len())?;
+        self.log(&format!("Writing {} manifests for {package_name}...", events.len()));
+        self.ensure_package_storage(&author, &package_name);
+        self.packages.get(&author).unwrap().insert(&package_name, &versions);
+        self.index_cids(&author, &package_name, &versions[published..]);
+        self.total_manifests += events.len() as u64;
+
+        let namespace = Self::generate_key(author, package_name);
+        self.append_package_leaves(&namespace, &versions[published..]);
+        self.emit(RegistryEvent::ManifestCreated(events));
+
+        return Self::charge_storage(initial_storage);
+    }
+
+    // Set the content type used by create_manifest when it is called with an empty content_type
+    // Keyed on the predecessor like set_publish_allowlist, so contract authors call this directly to set their own default
+    #[payable]
+    #[handle_result]
+    pub fn set_default_content_type(
+        &mut self,
+        // The content type to fall back to for the caller's future manifests
+        content_type: String
+    ) -> Result<(), RegistryError> {
+        self.assert_not_paused()?;
+
+        let initial_storage = near_sdk::env::storage_usage();
+        ensure(!content_type.is_empty(), RegistryError::EmptyContentType)?;
+        let content_type = ContentType::parse(&content_type).ok_or(RegistryError::InvalidContentType)?.to_string();
+        self.default_content_types.insert(&near_sdk::env::predecessor_account_id(), &content_type);
+        return Self::charge_storage(initial_storage);
+    }
+
+    // Estimates the storage cost in yoctoNEAR of publishing a manifest to a new package
+    // Counts every record create_manifest writes for the first package of a new author with the longest possible
+    // account id and an unused cid, so attaching it covers a publish with the same arguments and any excess is refunded
+    pub fn estimate_publish_cost(
+        &self,
+        // A string representing the name of a particular package
+        package_name: String,
+        // The version string of the prospective manifest
+        version: String,
+        // Specifies the type of content once resolved via the content id
+        content_type: String,
+        // The IPFS content id that contains the package manifest
+        cid: String,
+        // A list of named types in the package
+        types: Vec<String>,
+        // The encryption scheme and key reference if the content is encrypted
+        encryption: Option<EncryptionInfo>,
+        // The author, package name and version requirement of every package this version depends on
+        dependencies: Vec<Dependency>
+    ) -> U128 {
+        let manifest = Manifest {
+            version,
+            content_type,
+            cid,
+            types,
+            encryption,
+            created_at: 0,
+            updated_at: 0,
+            yanked: false,
+            downloads: 0,
+            dependencies
+        };
+
+        let manifest_bytes = near_sdk::borsh::to_vec(&manifest).unwrap().len() as u64;
+        let version_bytes = near_sdk::borsh::to_vec(&manifest.version).unwrap().len() as u64;
+        let cid_bytes = near_sdk::borsh::to_vec(&manifest.cid).unwrap().len() as u64;
+        let name_bytes = near_sdk::borsh::to_vec(&package_name).unwrap().len() as u64;
+        let types_bytes = near_sdk::borsh::to_vec(&manifest.types).unwrap().len() as u64;
+        // Strings and vectors carry a u32 length prefix
+        let author_bytes = 4 + MAX_ACCOUNT_ID_LENGTH;
+        let tag = near_sdk::borsh::to_vec(&PrefixKeys::ManifestV2).unwrap().len() as u64;
+        // Nested collections are prefixed with a tag and a sha256 hash, and namespaces are sha256 hashes
+        let nested = tag + 32;
+        let namespace_bytes = 4 + 32;
+
+        // The key and value size of every record written
+        let records = [
+            // The author's releases map and the package's version list inside it
+            (tag + author_bytes, 4 + nested),
+            (nested + name_bytes, 4 + manifest_bytes),
+            // The author's package name vector, which stores its length and prefix, and its first element
+            (tag + author_bytes, 8 + 4 + nested),
+            (nested + 8, name_bytes),
+            // The authors publishing under the package name
+            (tag + name_bytes, 4 + author_bytes),
+            // The global package index membership set and the index element
+            (tag + author_bytes + name_bytes, 0),
+            (tag + 8, author_bytes + name_bytes),
+            // The cid reverse lookup
+            (tag + cid_bytes, 4 + author_bytes + name_bytes + version_bytes),
+            // The hex encoded package root and the compiled types
+            (tag + namespace_bytes, 4 + 64),
+            (tag + namespace_bytes, types_bytes),
+            // The package's merkle tree, which stores its leaf count and prefix, and its only leaf keyed by level and position
+            (tag + namespace_bytes, 8 + 4 + nested),
+            (nested + 4 + 8, 4 + 32)
+        ];
+
+        let bytes = records.iter()
+            .map(|(key, value)| key + value + STORAGE_RECORD_OVERHEAD)
+            .sum::<u64>();
+
+        return U128(bytes as u128 * near_sdk::env::storage_byte_cost().as_yoctonear());
+    }
+
+    // Register the key that must sign manifests published under the caller with is_contract, replacing any previous key
+    // Only a transaction signed by the author account itself may register, so a contract relaying calls cannot swap the key
+    #[payable]
+    #[handle_result]
+    pub fn register_author_key(
+        &mut self,
+        // An ed25519 public key held by the author
+        key: PublicKey
+    ) -> Result<(), RegistryError> {
+        self.assert_not_paused()?;
+
+        let initial_storage = near_sdk::env::storage_usage();
+        let author = near_sdk::env::predecessor_account_id();
+        ensure(author == near_sdk::env::signer_account_id(), RegistryError::IndirectKeyRegistration)?;
+        ensure(key.curve_type() == CurveType::ED25519, RegistryError::UnsupportedKeyType)?;
+
+        self.log(&format!("Registering signing key for {author}..."));
+        self.author_keys.insert(&author, &key);
+        return Self::charge_storage(initial_storage);
+    }
+
+    // The key an author registered to sign manifests published with is_contract, if any
+    pub fn get_author_key(
+        &self,
+        // An account ID of the author
+        account_id: AccountId
+    ) -> Option<PublicKey> {
+        return self.author_keys.get(&account_id);
+    }
+
+    // Replace the accounts allowed to publish under the caller's namespace when they are not the author
+    // An empty list means only the author may publish, contract authors call this directly as the predecessor
+    #[payable]
+    #[handle_result]
+    pub fn set_publish_allowlist(
+        &mut self,
+        // The delegate signer accounts allowed to publish for the caller
+        accounts: Vec<AccountId>
+    ) -> Result<(), RegistryError> {
+        self.assert_not_paused()?;
+
+        let initial_storage = near_sdk::env::storage_usage();
+        self.publish_allowlists.insert(&near_sdk::env::predecessor_account_id(), &accounts);
+        return Self::charge_storage(initial_storage);
+    }
+
+    // The number of packages currently in the registry across every author
+    pub fn total_packages(&self) -> u64 {
+        return self.total_packages;
+    }
+
+    // The number of manifests currently in the registry across every package
+    pub fn total_manifests(&self) -> u64 {
+        return self.total_manifests;
+    }
+
+    // Lists the names of every package an account has published in publish order, empty if the account has none
+    pub fn get_packages(
+        &self,
+        // An account ID of the author who published the packages
+        account_id: AccountId
+    ) -> Vec<PackageName> {
+        return match self.package_names.get(&account_id) {
+            Some(names) => names.to_vec(),
+            None => Vec::new(),
+        };
+    }
+
+    // Lists a page of the names of an account's packages, empty past the end of the list
+    pub fn get_packages_paged(
+        &self,
+        // An account ID of the author who published the packages
+        account_id: AccountId,
+        // The index of the first package name to return
+        from_index: u64,
+        // The maximum number of package names to return
+        limit: u64
+    ) -> Vec<PackageName> {
+        let names = match self.package_names.get(&account_id) {
+            Some(names) => names,
+            None => return Vec::new(),
+        };
+
+        let start = from_index.min(names.len());
+        let end = from_index.saturating_add(limit).min(names.len());
+
+        return (start..end).map(|index| names.get(index).unwrap()).collect();
+    }
+
+    // Find the author, package name and version a cid was published under, None if no manifest uses it
+    // A cid reused by several manifests resolves to the earliest one still in the registry
+    pub fn lookup_by_cid(
+        &self,
+        // The IPFS content id of a manifest
+        cid: String
+    ) -> Option<CidOrigin> {
+        return self.cid_origins.get(&cid).and_then(|origins| origins.into_iter().next());
+    }
+
+    // Lists every account that has published a package under the given name, empty if the name is unknown
+    pub fn find_authors(
+        &self,
+        // A string representing the name of a particular package
+        package_name: String
+    ) -> Vec<AccountId> {
+        return self.package_authors.get(&package_name).unwrap_or_default();
+    }
+
+    // Page over every package in the registry with its manifests, for indexers taking a snapshot
+    // The index is append-only so pages stay stable, removed packages are skipped and a page may hold fewer than limit entries
+    pub fn export_packages(
+        &self,
+        // The position in the global package index to start from
+        from_index: u64,
+        // The maximum number of index positions to read
+        limit: u64
+    ) -> Vec<(AccountId, PackageName, Vec<Manifest>)> {
+        let start = from_index.min(self.package_index.len());
+        let end = from_index.saturating_add(limit).min(self.package_index.len());
+
+        return (start..end).filter_map(|index| {
+            let (author, package_name) = self.package_index.get(index).unwrap();
+            let versions = self.packages.get(&author).and_then(|releases| releases.get(&package_name))?;
+            return Some((author, package_name, versions));
+        }).collect();
+    }
+
+    // Search every author's packages for names starting with name_prefix, ignoring case, for typeahead
+    // Reads limit positions of the global package index like export_packages, so a page may hold fewer
+    // than limit matches and the next page starts at from_index + limit until package_index_len is reached
+    pub fn search_all(
+        &self,
+        // The start of the package names to match
+        name_prefix: String,
+        // The position in the global package index to start from
+        from_index: u64,
+        // The maximum number of index positions to read
+        limit: u64
+    ) -> Vec<(AccountId, PackageName)> {
+        let prefix = name_prefix.to_lowercase();
+        let start = from_index.min(self.package_index.len());
+        let end = from_index.saturating_add(limit).min(self.package_index.len());
+
+        return (start..end)
+            .map(|index| self.package_index.get(index).unwrap())
+            .filter(|(_, package_name)| package_name.to_lowercase().starts_with(&prefix))
+            .filter(|(author, package_name)| {
+                self.packages.get(author).map_or(false, |releases| releases.contains_key(package_name))
+            })
+            .collect();
+    }
+
+    // The length of the global package index paged over by export_packages, including removed packages
+    pub fn package_index_len(&self) -> u64 {
+        return self.package_index.len();
+    }
+
+    // Choose how versions of one of the author's packages are validated, packages are freeform by default
+    // The author is resolved like create_manifest and the scheme may be set before the first version is published
+    #[payable]
+    #[handle_result]
+    pub fn set_versioning_scheme(
+        &mut self,
+        // A string representing the name of a particular package
+        package_name: String,
+        // The validation applied to future versions
+        scheme: VersioningScheme,
+        // If a contract is calling this function the reference key can be the contract account if true or the signers account when false
+        is_contract: bool
+    ) -> Result<(), RegistryError> {
+        self.assert_not_paused()?;
+
+        let initial_storage = near_sdk::env::storage_usage();
+        ensure(Self::validate_package_name(&package_name), RegistryError::InvalidPackageName)?;
+
+        let author = self.resolve_author(is_contract)?;
+        let namespace = Self::generate_key(author, package_name);
+        self.versioning_schemes.insert(&namespace, &scheme);
+        return Self::charge_storage(initial_storage);
+    }
+
+    // Retrieves the cid of the last manifest for a particular package
+    // Returns None when the author, the package or any unyanked version is missing
+    pub fn get_latest_manifest(
+        &self,
+        // An account ID of the author who published the manifest
+        account_id: AccountId,
+        // A string representing the name of a particular package
+        package_name: String
+    ) -> Option<String> {
+        return self.get_latest_manifest_full(account_id, package_name).map(|m| m.cid);
+    }
+
+    // Retrieves the whole last manifest for a particular package including its version and content type
+    pub fn get_latest_manifest_full(
+        &self,
+        // An account ID of the author who published the manifest
+        account_id: AccountId,
+        // A string representing the name of a particular package
+        package_name: String
+    ) -> Option<Manifest> {
+        return self.find_versions(&account_id, &package_name).and_then(Self::latest_unyanked);
+    }
+
+    // Retrieves the manifest of a package with the newest created_at, ties go to the most recently inserted
+    // Differs from get_latest_manifest_full when versions were stored out of order, e.g. by migration
+    pub fn get_latest_by_time(
+        &self,
+        // An account ID of the author who published the manifest
+        account_id: AccountId,
+        // A string representing the name of a particular package
+        package_name: String
+    ) -> Option<Manifest> {
+        // max_by_key returns the last of several equal maxima
+        return self.find_versions(&account_id, &package_name)?
+            .into_iter()
+            .max_by_key(|m| m.created_at);
+    }
+
+    // Kept for clients written before get_latest_manifest returned None instead of reverting
+    pub fn get_latest_manifest_opt(
+        &self,
+        // An account ID of the author who published the manifest
+        account_id: AccountId,
+        // A string representing the name of a particular package
+        package_name: String
+    ) -> Option<String> {
+        return self.get_latest_manifest(account_id, package_name);
+    }
+
+    // Retrieves the cid of the last manifest for each of several packages of an author in one call
+    // Each entry pairs a requested package name with its latest cid, or None like get_latest_manifest_opt
+    pub fn get_latest_manifests(
+        &self,
+        // An account ID of the author who published the manifests
+        author: AccountId,
+        // The names of the packages to resolve
+        package_names: Vec<String>
+    ) -> Vec<(String, Option<String>)> {
+        let releases = self.packages.get(&author);
+
+        return package_names.into_iter().map(|package_name| {
+            let cid = releases.as_ref()
+                .and_then(|manifests| manifests.get(&package_name))
+                .and_then(Self::latest_unyanked)
+                .map(|m| m.cid);
+            return (package_name, cid);
+        }).collect();
+    }
+
+    // Get the cid of a single manifest given a version and package name
+    // Returns None if the author, package or version is missing, see try_get_manifest for the whole manifest
+    pub fn get_manifest(
+        &self,
+        // An account ID of the author who published the manifest
+        account_id: AccountId,
+        // A string representing the name of a particular package
+        package_name: String,
+        // The version string that is used to retreive the manifest
+        version: String
+    ) -> Option<String> {
+        return self.try_get_manifest(account_id, package_name, version).map(|m| m.cid);
+    }
+
+    // Tell consumers whether a version is usable, yanked, or missing without fetching the whole manifest
+    pub fn resolve_status(
+        &self,
+        // An account ID of the author who published the manifest
+        account_id: AccountId,
+        // A string representing the name of a particular package
+        package_name: String,
+        // The version string to resolve
+        version: String
+    ) -> VersionStatus {
+        return match self.try_get_manifest(account_id, package_name, version) {
+            None => VersionStatus::NotFound,
+            Some(m) if m.yanked => VersionStatus::Yanked,
+            Some(_) => VersionStatus::Active
+        };
+    }
+
+    // Get the full manifest for a version and package name
+    // Returns None instead of reverting when the author, package or version is missing
+    pub fn try_get_manifest(
+        &self,
+        // An account ID of the author who published the manifest
+        account_id: AccountId,
+        // A string representing the name of a particular package
+        package_name: String,
+        // The version string that is used to retreive the manifest
+        version: String
+    ) -> Option<Manifest> {
+        return self.find_versions(&account_id, &package_name)?
+            .into_iter()
+            .find(|m| m.version == version);
+    }
+
+    // Resolves a tag such as latest or beta to the manifest it points at
+    // Returns None when the tag is unset or its version no longer exists
+    pub fn get_by_tag(
+        &self,
+        // An account ID of the author who published the manifest
+        account_id: AccountId,
+        // A string representing the name of a particular package
+        package_name: String,
+        // The tag to resolve
+        tag: String
+    ) -> Option<Manifest> {
+        let namespace = Self::generate_key(account_id.clone(), package_name.clone());
+        let version = self.tags.get(&namespace)?.get(&tag)?;
+        return self.try_get_manifest(account_id, package_name, version);
+    }
+
+    // Lists every tag of a package with the version it points at, empty if the package has no tags
+    pub fn list_dist_tags(
+        &self,
+        // An account ID of the author who published the manifest
+        account_id: AccountId,
+        // A string representing the name of a particular package
+        package_name: String
+    ) -> Vec<(String, String)> {
+        let namespace = Self::generate_key(account_id, package_name);
+        return match self.tags.get(&namespace) {
+            Some(tags) => tags.to_vec(),
+            None => Vec::new(),
+        };
+    }
+
+    // Resolves a semver range such as ^0.1, ~1.2.3, >=2.0.0 or an exact version to the cid of the highest matching version
+    // Returns None when the range is invalid, nothing matches or the author or package is missing
+    // Yanked versions and versions that are not valid semver are never selected
+    pub fn resolve_range(
+        &self,
+        // An account ID of the author who published the manifest
+        author: AccountId,
+        // A string representing the name of a particular package
+        package_name: String,
+        // The version requirement to resolve
+        range: String
+    ) -> Option<String> {
+        let requirement = Requirement::parse(&range)?;
+        let versions = self.find_versions(&author, &package_name)?;
+
+        return versions.into_iter()
+            .filter(|m| !m.yanked)
+            .filter_map(|m| Version::parse(&m.version).map(|v| (v, m)))
+            .filter(|(v, _)| requirement.matches(v))
+            .max_by(|a, b| a.0.cmp(&b.0))
+            .map(|(_, m)| m.cid);
+    }
+
+    // Resolves a version to its cid and the content type needed to interpret it
+    // Returns None instead of reverting when the author, package or version is missing
+    pub fn resolve(
+        &self,
+        // An account ID of the author who published the manifest
+        account_id: AccountId,
+        // A string representing the name of a particular package
+        package_name: String,
+        // The version string that is used to retreive the manifest
+        version: String
+    ) -> Option<(String, String)> {
+        return self.try_get_manifest(account_id, package_name, version)
+            .map(|m| (m.cid, m.content_type));
+    }
+
+    // Lists every published version of a package in the order they were published
+    // Returns None when the author or package is missing
+    pub fn list_versions(
+        &self,
+        // An account ID of the author who published the manifest
+        account_id: AccountId,
+        // A string representing the name of a particular package
+        package_name: String
+    ) -> Option<Vec<String>> {
+        let versions = self.find_versions(&account_id, &package_name)?;
+        return Some(versions.into_iter().map(|m| m.version).collect());
+    }
+
+    // Retrieves every manifest of a package with all of its fields in the order they were published
+    // Returns None when the author or package is missing
+    pub fn get_manifest_history(
+        &self,
+        // An account ID of the author who published the manifest
+        account_id: AccountId,
+        // A string representing the name of a particular package
+        package_name: String
+    ) -> Option<Vec<Manifest>> {
+        return self.find_versions(&account_id, &package_name);
+    }
+
+    // Lists the manifests of a package whose content type matches, empty if none match
+    // Returns None when the author or package is missing
+    pub fn list_versions_by_type(
+        &self,
+        // An account ID of the author who published the manifest
+        account_id: AccountId,
+        // A string representing the name of a particular package
+        package_name: String,
+        // The content type that returned manifests must have
+        content_type: String
+    ) -> Option<Vec<Manifest>> {
+        let versions = self.find_versions(&account_id, &package_name)?;
+        return Some(versions.into_iter().filter(|m| m.content_type == content_type).collect());
+    }
+
+    // Lists the manifests of a page of an author's packages whose content type matches, in the order of get_packages
+    // and then publish order, content types are compared in their stored form so known types match case-insensitively
+    pub fn author_manifests_by_content_type(
+        &self,
+        // An account ID of the author who published the manifests
+        account_id: AccountId,
+        // The content type that returned manifests must have
+        content_type: String,
+        // The index of the first package whose manifests are searched
+        from_index: u64,
+        // The maximum number of packages whose manifests are searched
+        limit: u64
+    ) -> Vec<(PackageName, Manifest)> {
+        let content_type = match ContentType::parse(&content_type) {
+            Some(content_type) => content_type.to_string(),
+            None => return Vec::new(),
+        };
+
+        return self.get_packages_paged(account_id.clone(), from_index, limit)
+            .into_iter()
+            .flat_map(|package_name| {
+                return self.find_versions(&account_id, &package_name)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter(|m| m.content_type == content_type)
+                    .map(move |m| (package_name.clone(), m));
+            })
+            .collect();
+    }
+
+    // Retrieves every manifest whose version falls between two semantic versions (inclusive)
+    // Versions that are not valid semver are skipped and results are sorted in ascending order
+    // Returns None when the author or package is missing or either bound is not valid semver
+    pub fn get_versions_between(
+        &self,
+        // An account ID of the author who published the manifest
+        account_id: AccountId,
+        // A string representing the name of a particular package
+        package_name: String,
+        // The lowest semantic version to include
+        low: String,
+        // The highest semantic version to include
+        high: String
+    ) -> Option<Vec<Manifest>> {
+        let versions = self.find_versions(&account_id, &package_name)?;
+        let low = Version::parse(&low)?;
+        let high = Version::parse(&high)?;
+
+        if low > high {
+            return Some(Vec::new());
+        }
+
+        let mut matches = versions
+            .into_iter()
+            .filter_map(|m| Version::parse(&m.version).map(|v| (v, m)))
+            .filter(|(v, _)| *v >= low && *v <= high)
+            .collect::<Vec<(Version, Manifest)>>();
+
+        matches.sort_by(|a, b| a.0.cmp(&b.0));
+
+        return Some(matches.into_iter().map(|(_, m)| m).collect());
+    }
+
+    // Lists every version string that appears more than once for a package
+    // Historical data may contain duplicates which makes get_manifest resolve only the first match
+    // Returns None when the author or package is missing
+    pub fn detect_duplicate_versions(
+        &self,
+        // An account ID of the author who published the manifest
+        account_id: AccountId,
+        // A string representing the name of a particular package
+        package_name: String
+    ) -> Option<Vec<String>> {
+        let versions = self.find_versions(&account_id, &package_name)?;
+        let mut duplicates: Vec<String> = Vec::new();
+
+        for (i, m) in versions.iter().enumerate() {
+            if duplicates.contains(&m.version) {
+                continue;
+            }
+
+            if versions[i + 1..].iter().any(|other| other.version == m.version) {
+                duplicates.push(m.version.clone());
+            }
+        }
+
+        return Some(duplicates);
+    }
+
+    // Lists every cid that more than one version of a package points to, along with the versions sharing it
+    // Returns None when the author or package is missing
+    pub fn intra_package_cid_duplicates(
+        &self,
+        // An account ID of the author who published the manifest
+        account_id: AccountId,
+        // A string representing the name of a particular package
+        package_name: String
+    ) -> Option<Vec<(String, Vec<String>)>> {
+        let mut shared: Vec<(String, Vec<String>)> = Vec::new();
+        for m in self.find_versions(&account_id, &package_name)? {
+            match shared.iter_mut().find(|(cid, _)| *cid == m.cid) {
+                Some((_, versions)) => versions.push(m.version),
+                None => shared.push((m.cid, vec![m.version])),
+            }
+        }
+
+        return Some(shared.into_iter().filter(|(_, versions)| versions.len() > 1).collect());
+    }
+
+    // Retrieves the hex encoded merkle root committing to every manifest of a package in publish order
+    // Leaves are sha256(0x00 || borsh(manifest)) with downloads zeroed, parents are sha256(0x01 || left || right)
+    // and an unpaired node is promoted as is
+    // Returns None when the author or package is missing
+    pub fn get_package_root(
+        &self,
+        // An account ID of the author who published the manifest
+        account_id: AccountId,
+        // A string representing the name of a particular package
+        package_name: String
+    ) -> Option<String> {
+        self.find_versions(&account_id, &package_name)?;
+
+        let namespace = Self::generate_key(account_id, package_name);
+        return Some(self.package_roots.get(&namespace).unwrap_or_default());
+    }
+
+    // Builds an inclusion proof for a single version against get_package_root
+    // Returns None if the author, package or version was not found
+    pub fn get_manifest_proof(
+        &self,
+        // An account ID of the author who published the manifest
+        account_id: AccountId,
+        // A string representing the name of a particular package
+        package_name: String,
+        // The version string that is used to retreive the manifest
+        version: String
+    ) -> Option<MerkleProof> {
+        let versions = self.find_versions(&account_id, &package_name)?;
+        let index = versions.iter().position(|m| m.version == version)?;
+        let leaf = merkle::to_hex(&Self::manifest_leaf(&versions[index]));
+
+        let namespace = Self::generate_key(account_id, package_name);
+        let steps = self.package_tree(&namespace)
+            .proof(index as u64)
+            .into_iter()
+            .map(|(hash, is_left)| ProofStep {
+                hash: merkle::to_hex(&hash),
+                is_left
+            })
+            .collect();
+
+        return Some(MerkleProof {
+            leaf,
+            index: index as u64,
+            steps
+        });
+    }
+
+    // Update a particular manifest file given the package name and version
+    #[payable]
+    #[handle_result]
+    pub fn update_manifest(
+        &mut self,
+        // A string representing the name of a particular package
+        package_name: String,
+        // The version string that is used to retreive the manifest
+        version: String,
+        // The new content type if changed
+        content_type: String,
+        // The IPFS content to replace the existing one
+        cid: String
+    ) -> Result<(), RegistryError> {
+        self.assert_not_paused()?;
+
+        let initial_storage = near_sdk::env::storage_usage();
+        // Packages are looked up under the signer so only the author can update their own releases
+        self.apply_manifest_update(near_sdk::env::signer_account_id(), package_name, version, content_type, cid)?;
+        return Self::charge_storage(initial_storage);
+    }
+
+    // Publish a version of a package, or update its cid and content type in place if the version already exists
+    // Lets CI make sure a version ends up with the right cid without checking whether it was published before
+    #[payable]
+    #[handle_result]
+    pub fn upsert_manifest(
+        &mut self,
+        // A string representing the name of a particular package
+        package_name: String,
+        // The version string to publish or update
+        version: String,
+        // Specifies the type of content once resolved via the content id, an empty string uses the author's default
+        content_type: String,
+        // The IPFS content id that contains the package manifest
+        cid: String,
+        // If a contract is calling this function the reference key can be the contract account if true or the signers account when false
+        is_contract: bool,
+        // An ed25519 signature over the release by the author's registered key, required with is_contract once a key is registered
+        signature: Option<Vec<u8>>
+    ) -> Result<(), RegistryError> {
+        self.assert_not_paused()?;
+
+        let initial_storage = near_sdk::env::storage_usage();
+        let author = self.resolve_author(is_contract)?;
+        let exists = self.packages.get(&author)
+            .and_then(|releases| releases.get(&package_name))
+            .map_or(false, |versions| versions.iter().any(|m| m.version == version));
+
+        if !exists {
+            return self.create_manifest(package_name, version, content_type, cid, Vec::new(), None, is_contract, signature, Vec::new());
+        }
+
+        if is_contract {
+            self.ensure_author_signature(&author, &package_name, &version, &cid, &signature)?;
+        }
+
+        let content_type = match content_type.is_empty() {
+            true => self.default_content_types.get(&author).ok_or(RegistryError::MissingContentType)?,
+            false => content_type,
+        };
+
+        self.apply_manifest_update(author, package_name, version, content_type, cid)?;
+        return Self::charge_storage(initial_storage);
+    }
+
+    // Replaces the cid and content type of an existing version of an author's package
+    fn apply_manifest_update(
+        &mut self,
+        author: AccountId,
+        package_name: String,
+        version: String,
+        content_type: String,
+        cid: String
+    ) -> Result<(), RegistryError> {
+        let content_type = ContentType::parse(&content_type).ok_or(RegistryError::InvalidContentType)?.to_string();
+        ensure(Self::validate_cid(&cid), RegistryError::InvalidCid)?;
+        self.ensure_cid_not_blocked(&cid)?;
+
+        let mut manifests = self.get_releases(&author)?;
+        let versions = manifests.get(&package_name).ok_or(RegistryError::PackageNotFound)?;
+        let previous = versions.iter()
+            .find(|m| m.version == version)
+            .cloned()
+            .ok_or(RegistryError::VersionNotFound)?;
+
+        self.log(&format!("Updating existing manifest for {package_name} and {version}..."));
+
+        let v = versions.clone().into_iter().map(|mut m| {
+                if m.version == version {
+                    m.cid = cid.clone();
+                    m.content_type = content_type.clone();
+                    m.updated_at = near_sdk::env::block_timestamp();
+                }
+
+                return m;
+            }
+        ).collect::<Vec<Manifest>>();
+
+        manifests.insert(&package_name, &v);
+
+        let index = v.iter().position(|m| m.version == version).unwrap();
+        let updated = &v[index];
+        self.unindex_cids(&author, &package_name, std::slice::from_ref(&previous));
+        self.index_cids(&author, &package_name, std::slice::from_ref(updated));
+
+        let event = ManifestEventData {
+            author: author.clone(),
+            package_name: package_name.clone(),
+            version,
+            cid
+        };
+
+        let namespace = Self::generate_key(author, package_name);
+        self.update_package_leaf(&namespace, index, &v[index]);
+        self.emit(RegistryEvent::ManifestUpdated(vec![event]));
+        return Ok(());
+    }
+
+    // Mark one of the signer's versions as yanked to discourage its use without deleting it
+    #[handle_result]
+    pub fn set_yanked(
+        &mut self,
+        // A string representing the name of a particular package
+        package_name: String,
+        // The version string of the manifest to yank or restore
+        version: String,
+        // Whether the version is yanked
+        yanked: bool
+    ) -> Result<(), RegistryError> {
+        self.assert_not_paused()?;
+
+        let author = near_sdk::env::signer_account_id();
+        let mut manifests = self.get_releases(&author)?;
+        let mut versions = manifests.get(&package_name).ok_or(RegistryError::PackageNotFound)?;
+        let index = versions.iter()
+            .position(|m| m.version == version)
+            .ok_or(RegistryError::VersionNotFound)?;
+
+        self.log(&format!("Setting yanked to {yanked} for {package_name} and {version}..."));
+        versions[index].yanked = yanked;
+        manifests.insert(&package_name, &versions);
+
+        let namespace = Self::generate_key(author, package_name);
+        self.update_package_leaf(&namespace, index, &versions[index]);
+        return Ok(());
+    }
+
+    // Count a download of a version, anyone may report one so the count is a popularity signal rather than a proof
+    #[handle_result]
+    pub fn record_download(
+        &mut self,
+        // An account ID of the author who published the manifest
+        author: AccountId,
+        // A string representing the name of a particular package
+        package_name: String,
+        // The version string of the downloaded manifest
+        version: String
+    ) -> Result<(), RegistryError> {
+        self.assert_not_paused()?;
+
+        let mut manifests = self.get_releases(&author)?;
+        let mut versions = manifests.get(&package_name).ok_or(RegistryError::PackageNotFound)?;
+        let manifest = versions.iter_mut()
+            .find(|m| m.version == version)
+            .ok_or(RegistryError::VersionNotFound)?;
+
+        manifest.downloads += 1;
+        manifests.insert(&package_name, &versions);
+        return Ok(());
+    }
+
+    // Point a tag of one of the signer's packages at an existing version, moving the tag if it is already set
+    #[payable]
+    #[handle_result]
+    pub fn set_tag(
+        &mut self,
+        // A string representing the name of a particular package
+        package_name: String,
+        // The tag to set, e.g. latest, beta or lts
+        tag: String,
+        // The version string the tag points at
+        version: String
+    ) -> Result<(), RegistryError> {
+        self.assert_not_paused()?;
+
+        let initial_storage = near_sdk::env::storage_usage();
+        ensure(!tag.is_empty(), RegistryError::EmptyTag)?;
+        ensure(tag.len() <= MAX_TAG_LENGTH, RegistryError::TagTooLong)?;
+
+        let author = near_sdk::env::signer_account_id();
+        let manifests = self.get_releases(&author)?;
+        let versions = manifests.get(&package_name).ok_or(RegistryError::PackageNotFound)?;
+        ensure(versions.iter().any(|m| m.version == version), RegistryError::VersionNotFound)?;
+
+        self.log(&format!("Tagging {package_name} {version} as {tag}..."));
+        let namespace = Self::generate_key(author, package_name);
+        let mut tags = self.package_tags(&namespace);
+        tags.insert(&tag, &version);
+        self.tags.insert(&namespace, &tags);
+        return Self::charge_storage(initial_storage);
+    }
+
+    // Remove a tag from one of the signer's packages, the version it pointed at is left in place
+    #[handle_result]
+    pub fn remove_dist_tag(
+        &mut self,
+        // A string representing the name of a particular package
+        package_name: String,
+        // The tag to remove
+        tag: String
+    ) -> Result<(), RegistryError> {
+        self.assert_not_paused()?;
+
+        let author = near_sdk::env::signer_account_id();
+        let manifests = self.get_releases(&author)?;
+        ensure(manifests.contains_key(&package_name), RegistryError::PackageNotFound)?;
+
+        let namespace = Self::generate_key(author, package_name.clone());
+        let mut tags = self.tags.get(&namespace).ok_or(RegistryError::TagNotFound)?;
+        ensure(tags.remove(&tag).is_some(), RegistryError::TagNotFound)?;
+
+        self.log(&format!("Removing tag {tag} from {package_name}..."));
+        if tags.is_empty() {
+            self.tags.remove(&namespace);
+        }
+
+        return Ok(());
+    }
+
+    // Remove a single version of one of the signer's packages
+    // Removing the only remaining version removes the package entirely
+    #[handle_result]
+    pub fn delete_manifest(
+        &mut self,
+        // A string representing the name of a particular package
+        package_name: String,
+        // The version string of the manifest to remove
+        version: String
+    ) -> Result<(), RegistryError> {
+        self.assert_not_paused()?;
+
+        let author = near_sdk::env::signer_account_id();
+        let mut manifests = self.get_releases(&author)?;
+        let versions = manifests.get(&package_name).ok_or(RegistryError::PackageNotFound)?;
+        let index = versions.iter()
+            .position(|m| m.version == version)
+            .ok_or(RegistryError::VersionNotFound)?;
+
+        self.log(&format!("Deleting manifest for {package_name} and {version}..."));
+        self.remove_version(&author, &mut manifests, &package_name, versions, index);
+
+        return Ok(());
+    }
+
+    // Remove a version together with its cid index entry, clearing the package when it held the only version
+    fn remove_version(
+        &mut self,
+        author: &AccountId,
+        manifests: &mut Releases,
+        package_name: &PackageName,
+        mut versions: Vec<Manifest>,
+        index: usize
+    ) {
+        let removed = versions.remove(index);
+        self.unindex_cids(author, package_name, &[removed]);
+        self.total_manifests -= 1;
+
+        if versions.is_empty() {
+            self.clear_package(author, manifests, package_name);
+        } else {
+            manifests.insert(package_name, &versions);
+            let namespace = Self::generate_key(author.clone(), package_name.clone());
+            match index == versions.len() {
+                true => self.pop_package_leaf(&namespace),
+                false => self.refresh_package_root(&namespace, &versions),
+            }
+        }
+    }
+
+    // Take back the signer's most recent publish of a package while it is within the owner's undo window
+    // The most recent publish is the version with the newest created_at, like get_latest_by_time
+    #[handle_result]
+    pub fn undo_last_publish(
+        &mut self,
+        // A string representing the name of a particular package
+        package_name: String
+    ) -> Result<(), RegistryError> {
+        self.assert_not_paused()?;
+
+        let author = near_sdk::env::signer_account_id();
+        let mut manifests = self.get_releases(&author)?;
+        let versions = manifests.get(&package_name).ok_or(RegistryError::PackageNotFound)?;
+        // max_by_key returns the last of several equal maxima
+        let (index, latest) = versions.iter()
+            .enumerate()
+            .max_by_key(|(_, m)| m.created_at)
+            .ok_or(RegistryError::VersionNotFound)?;
+        let elapsed = near_sdk::env::block_timestamp().saturating_sub(latest.created_at);
+        ensure(elapsed < self.config.undo_window_ns, RegistryError::UndoWindowElapsed)?;
+
+        self.log(&format!("Undoing publish of {package_name} {}...", latest.version));
+        self.remove_version(&author, &mut manifests, &package_name, versions, index);
+
+        return Ok(());
+    }
+
+    // Remove one of the signer's packages along with every version of it
+    // Attestations belong to their attestors and are left in place, so they apply again if the name is republished
+    #[handle_result]
+    pub fn remove_package(
+        &mut self,
+        // A string representing the name of a particular package
+        package_name: String
+    ) -> Result<(), RegistryError> {
+        self.assert_not_paused()?;
+
+        let author = near_sdk::env::signer_account_id();
+        let mut manifests = self.get_releases(&author)?;
+        let versions = manifests.get(&package_name).ok_or(RegistryError::PackageNotFound)?;
+
+        self.log(&format!("Removing package {package_name}..."));
+        self.unindex_cids(&author, &package_name, &versions);
+        self.total_manifests -= versions.len() as u64;
+        self.clear_package(&author, &mut manifests, &package_name);
+        return Ok(());
+    }
+
+    // Erase up to limit of an author's packages starting at from_index, with every version, index entry and
+    // attestation recorded against them, callable by the author or the owner
+    // Returns how many packages remain, once none do the author's settings and signing key are erased as well
+    #[handle_result]
+    pub fn purge_author_data(
+        &mut self,
+        // An account ID of the author whose data is erased
+        author: AccountId,
+        // The index in the author's package list of the first package to erase
+        from_index: u64,
+        // The maximum number of packages to erase in this call
+        limit: u64
+    ) -> Result<u64, RegistryError> {
+        self.assert_not_paused()?;
+        ensure(
+            near_sdk::env::signer_account_id() == author || near_sdk::env::predecessor_account_id() == self.owner_id,
+            RegistryError::NotAuthorOrOwner
+        )?;
+
+        let names = self.get_packages_paged(author.clone(), from_index, limit);
+        if let Some(mut manifests) = self.packages.get(&author) {
+            for package_name in names {
+                self.log(&format!("Purging package {package_name} of {author}..."));
+                let versions = manifests.get(&package_name).unwrap_or_default();
+                self.unindex_cids(&author, &package_name, &versions);
+                self.total_manifests -= versions.len() as u64;
+                self.clear_attestations(&author, &Self::generate_key(author.clone(), package_name.clone()));
+                self.clear_package(&author, &mut manifests, &package_name);
+            }
+        }
+
+        let remaining = self.package_names.get(&author).map_or(0, |names| names.len());
+        if remaining == 0 {
+            self.packages.remove(&author);
+            self.package_names.remove(&author);
+            self.default_content_types.remove(&author);
+            self.publish_allowlists.remove(&author);
+            self.author_keys.remove(&author);
+            self.author_attestations.remove(&author);
+        }
+
+        return Ok(remaining);
+    }
+
+    // Move every version of one of the signer's packages into another of their packages and remove the source
+    // Attestations made against the source package stay keyed to its old name
+    #[payable]
+    #[handle_result]
+    pub fn merge_packages(
+        &mut self,
+        // The package whose versions are moved and which is removed afterwards
+        from_package: String,
+        // The package receiving the versions
+        into_package: String,
+        // How to resolve versions present in both packages
+        strategy: MergeStrategy
+    ) -> Result<(), RegistryError> {
+        self.assert_not_paused()?;
+
+        let initial_storage = near_sdk::env::storage_usage();
+        let author = near_sdk::env::signer_account_id();
+        let mut manifests = self.get_releases(&author)?;
+        ensure(from_package != into_package, RegistryError::SelfMerge)?;
+        let from_versions = manifests.get(&from_package).ok_or(RegistryError::PackageNotFound)?;
+        let mut into_versions = manifests.get(&into_package).ok_or(RegistryError::PackageNotFound)?;
+
+        self.log(&format!("Merging {from_package} into {into_package}..."));
+        // Skipped and overwritten versions of the source disappear from the totals
+        self.total_manifests -= (from_versions.len() + into_versions.len()) as u64;
+        self.unindex_cids(&author, &from_package, &from_versions);
+        self.unindex_cids(&author, &into_package, &into_versions);
+
+        for m in from_versions {
+            match into_versions.iter().position(|v| v.version == m.version) {
+                None => into_versions.push(m),
+                Some(i) => match strategy {
+                    MergeStrategy::Skip => self.log(&format!("Skipping existing version {}", m.version)),
+                    MergeStrategy::Overwrite => into_versions[i] = m,
+                    MergeStrategy::Error => return Err(RegistryError::MergeConflict),
+                },
+            }
+        }
+
+        self.ensure_version_capacity(into_versions.len())?;
+        manifests.insert(&into_package, &into_versions);
+        manifests.remove(&from_package);
+        self.total_manifests += into_versions.len() as u64;
+        self.index_cids(&author, &into_package, &into_versions);
+        self.unindex_package_name(&author, &from_package);
+        self.package_transfers.remove(&(author.clone(), from_package.clone()));
+
+        let from_namespace = Self::generate_key(author.clone(), from_package);
+        let into_namespace = Self::generate_key(author, into_package);
+        self.clear_tags(&from_namespace);
+
+        if let Some(mut from_types) = self.compiled_types.remove(&from_namespace) {
+            let mut into_types = self.compiled_types.get(&into_namespace).unwrap_or_default();
+            into_types.append(&mut from_types);
+            self.compiled_types.insert(&into_namespace, &into_types);
+        }
+
+        self.clear_package_tree(&from_namespace);
+        self.refresh_package_root(&into_namespace, &into_versions);
+        return Self::charge_storage(initial_storage);
+    }
+
+    // Offer one of the signer's packages to another account, for teams migrating accounts
+    // Nothing moves until new_owner accepts, offering again replaces the pending offer
+    #[payable]
+    #[handle_result]
+    pub fn offer_package_transfer(
+        &mut self,
+        // A string representing the name of a particular package
+        package_name: String,
+        // The account that may accept the package and become its author
+        new_owner: AccountId
+    ) -> Result<(), RegistryError> {
+        self.assert_not_paused()?;
+
+        let initial_storage = near_sdk::env::storage_usage();
+        let author = near_sdk::env::signer_account_id();
+        let manifests = self.get_releases(&author)?;
+        ensure(manifests.contains_key(&package_name), RegistryError::PackageNotFound)?;
+
+        self.log(&format!("Offering {package_name} to {new_owner}..."));
+        self.package_transfers.insert(&(author, package_name), &new_owner);
+        return Self::charge_storage(initial_storage);
+    }
+
+    // Accept a package offered to the signer and move every version of it under the signer's account
+    // Attestations, self-attestation policy and challenges stay keyed to the old author and are not carried over
+    #[payable]
+    #[handle_result]
+    pub fn accept_package_transfer(
+        &mut self,
+        // The account currently publishing the package
+        author: AccountId,
+        // A string representing the name of a particular package
+        package_name: String
+    ) -> Result<(), RegistryError> {
+        self.assert_not_paused()?;
+
+        let initial_storage = near_sdk::env::storage_usage();
+        let new_owner = near_sdk::env::signer_account_id();
+        let offer = (author.clone(), package_name.clone());
+        ensure(
+            self.package_transfers.get(&offer).as_ref() == Some(&new_owner),
+            RegistryError::NoPendingTransfer
+        )?;
+
+        let mut manifests = self.get_releases(&author)?;
+        ensure(manifests.contains_key(&package_name), RegistryError::PackageNotFound)?;
+        ensure(
+            !self.packages.get(&new_owner).map_or(false, |releases| releases.contains_key(&package_name)),
+            RegistryError::PackageNameTaken
+        )?;
+
+        if !self.packages.contains_key(&new_owner) {
+            self.packages.insert(
+                &new_owner,
+                &LookupMap::new(Self::nested_prefix(PrefixKeys::ManifestV2, &new_owner))
+            );
+        }
+
+        let mut new_manifests = self.packages.get(&new_owner).unwrap();
+
+        self.log(&format!("Transferring {package_name} to {new_owner}..."));
+        self.package_transfers.remove(&offer);
+        let versions = manifests.remove(&package_name).unwrap();
+        new_manifests.insert(&package_name, &versions);
+        self.unindex_package_name(&author, &package_name);
+        self.index_package_name(&new_owner, &package_name);
+        self.unindex_cids(&author, &package_name, &versions);
+        self.index_cids(&new_owner, &package_name, &versions);
+
+        let old_namespace = Self::generate_key(author, package_name.clone());
+        let new_namespace = Self::generate_key(new_owner, package_name);
+        self.clear_tags(&old_namespace);
+
+        if let Some(types) = self.compiled_types.remove(&old_namespace) {
+            self.compiled_types.insert(&new_namespace, &types);
+        }
+
+        if let Some(scheme) = self.versioning_schemes.remove(&old_namespace) {
+            self.versioning_schemes.insert(&new_namespace, &scheme);
+        }
+
+        self.clear_package_tree(&old_namespace);
+        self.refresh_package_root(&new_namespace, &versions);
+        return Self::charge_storage(initial_storage);
+    }
+
+    // Add an attestation for a package that exists inside of the registry
+    #[payable]
+    #[handle_result]
+    pub fn create_attestation(
+        &mut self,
+        // A string representing the name of a particular package
+        package_name: String,
+        // An account ID of the author who published the manifest
+        author: AccountId,
+        // An IPFS content ID that contains the attestation data
+        cid: String,
+        // An optional short justification for the claim
+        note: Option<String>,
+        // An optional ed25519 signature by the signer's access key over the bytes of the package's latest manifest cid
+        signature: Option<Vec<u8>>,
+        // Attest as the predecessor contract rather than the signer
+        is_contract: bool,
+        // An identifier for the contract's claim, required when attesting as a contract
+        identifier: Option<String>,
+        // The kind of claim being made, e.g. "build-provenance" or "security-audit"
+        claim_type: String,
+        // The version of the schema the attestation data follows
+        schema_version: String,
+        // Block timestamp in nanoseconds after which the claim is no longer valid, zero if it never expires
+        expires_at: u64
+    ) -> Result<(), RegistryError> {
+        self.assert_not_paused()?;
+
+        let initial_storage = near_sdk::env::storage_usage();
+        self.write_attestation(
+            package_name,
+            author,
+            cid,
+            note,
+            signature,
+            is_contract,
+            identifier,
+            claim_type,
+            schema_version,
+            expires_at
+        )?;
+        return Self::charge_storage(initial_storage);
+    }
+
+    // Validates and stores a new attestation without charging for storage, see write_manifest
+    fn write_attestation(
+        &mut self,
+        package_name: String,
+        author: AccountId,
+        cid: String,
+        note: Option<String>,
+        signature: Option<Vec<u8>>,
+        is_contract: bool,
+        identifier: Option<String>,
+        claim_type: String,
+        schema_version: String,
+        expires_at: u64
+    ) -> Result<(), RegistryError> {
+        ensure(
+            note.as_ref().map_or(true, |n| n.len() <= MAX_NOTE_LENGTH),
+            RegistryError::NoteTooLong
+        )?;
+        ensure(Self::validate_cid(&cid), RegistryError::InvalidCid)?;
+
+        let attestor = Self::resolve_attestor(is_contract)?;
+
+        // Contracts have no access key of their own, so they are identified by account and identifier alone
+        if is_contract {
+            ensure(identifier.as_ref().map_or(false, |i| !i.is_empty()), RegistryError::MissingAttestorIdentifier)?;
+            ensure(signature.is_none(), RegistryError::InvalidAttestationSignature)?;
+        }
+
+        let manifests = self.get_releases(&author)?;
+        ensure(manifests.contains_key(&package_name), RegistryError::PackageNotFound)?;
+        let hash = Self::generate_key(author.clone(), package_name.clone());
+
+        if let Some(signature) = signature {
+            let latest = manifests.get(&package_name).and_then(|v| v.last().map(|m| m.cid.clone()));
+            ensure(latest.is_some(), RegistryError::PackageNotFound)?;
+            ensure(
+                Self::verify_signature(&near_sdk::env::signer_account_pk(), &signature, latest.unwrap().as_bytes()),
+                RegistryError::InvalidAttestationSignature
+            )?;
+        }
+
+        // Self-attestation needs the registry to allow it and the author not to have forbidden it for the package
+        if !self.config.self_attestation_allowed || !self.allow_self_attestation.get(&hash).unwrap_or(true) {
+            ensure(attestor != author, RegistryError::SelfAttestation)?;
+        }
+
+        let attest = match is_contract {
+            true => Attestation {
+                pubkey: None,
+                identifier,
+                cid,
+                note,
+                reconfirmed_at_ns: 0,
+                claim_type,
+                schema_version,
+                expires_at
+            },
+            false => Attestation {
+                pubkey: Some(near_sdk::env::signer_account_pk()),
+                identifier: None,
+                cid,
+                note,
+                reconfirmed_at_ns: 0,
+                claim_type,
+                schema_version,
+                expires_at
+            },
+        };
+
+        let existing = self.attestations.get(&attestor)
+            .and_then(|at| at.get(&hash))
+            .unwrap_or_default();
+
+        // The same key claiming the same cid again would only inflate counts and storage
+        ensure(
+            !existing.iter().any(|a| {
+                a.pubkey == attest.pubkey && a.identifier == attest.identifier && a.cid == attest.cid
+            }),
+            RegistryError::DuplicateAttestation
+        )?;
+
+        if !self.attestations.contains_key(&attestor) {
+            self.attestations.insert(
+                &attestor,
+                &LookupMap::new(Self::nested_prefix(PrefixKeys::AttestorAttestations, &attestor))
+            );
+
+            self.log(&format!("Creating attestation storage..."));
+        }
+
+        let mut at = self.attestations.get(&attestor).unwrap();
+        if !at.contains_key(&hash) {
+            at.insert(&hash, &mut Vec::new());
+        }
+
+        let total = self.author_attestations.get(&author).unwrap_or(0);
+        self.author_attestations.insert(&author, &(total + 1));
+
+        let event = AttestationEventData {
+            attestor: attestor.clone(),
+            author: author.clone(),
+            package_name: package_name.clone(),
+            cid: attest.cid.clone()
+        };
+
+        let mut user_atts = self.get_attestation_bucket(
+            manifests,
+            attestor.clone(),
+            author,
+            package_name
+        )?;
+
+        user_atts.push(attest);
+        let mut at = self.attestations.get(&attestor).unwrap();
+        at.insert(&hash, &user_atts);
+        self.index_attestor(&hash, &attestor);
+        self.emit(RegistryEvent::AttestationCreated(vec![event]));
+
+        return Ok(());
+    }
+
+    // Publish a manifest and record the signer's own attestation over it in one call
+    // An error from either write reverts both, and the deposit is charged once for everything written
+    #[payable]
+    #[handle_result]
+    pub fn publish_and_attest(
+        &mut self,
+        // A string representing the name of a particular package
+        package_name: String,
+        // The version string that can represent either semantic versioning or any other format
+        version: String,
+        // Specifies the type of content once resolved via the content id, an empty string uses the author's default
+        content_type: String,
+        // The IPFS content id that contains the package manifest
+        cid: String,
+        // An IPFS content ID that contains the attestation data
+        attestation_cid: String,
+        // The kind of claim being made, e.g. "build-provenance"
+        claim: String
+    ) -> Result<(), RegistryError> {
+        self.assert_not_paused()?;
+
+        let initial_storage = near_sdk::env::storage_usage();
+        let author = near_sdk::env::signer_account_id();
+        self.write_manifest(package_name.clone(), version, content_type, cid, Vec::new(), None, false, None, Vec::new())?;
+        self.write_attestation(
+            package_name,
+            author,
+            attestation_cid,
+            None,
+            None,
+            false,
+            None,
+            claim,
+            String::new(),
+            0
+        )?;
+        return Self::charge_storage(initial_storage);
+    }
+
+    // Withdraw one of the signer's own attestations on a package by its index
+    #[handle_result]
+    pub fn revoke_attestation(
+        &mut self,
+        // The package name that the signer has made a claim against
+        package_name: String,
+        // The author for a particular package
+        author: AccountId,
+        // The index of the attestation to remove
+        index: usize,
+        // Revoke an attestation made by the predecessor contract rather than the signer
+        is_contract: bool
+    ) -> Result<(), RegistryError> {
+        self.assert_not_paused()?;
+
+        let attestor = Self::resolve_attestor(is_contract)?;
+        let manifests = self.get_releases(&author)?;
+        let mut user_atts = self.get_attestation_bucket(
+            manifests,
+            attestor.clone(),
+            author.clone(),
+            package_name.clone()
+        )?;
+
+        ensure(index < user_atts.len(), RegistryError::AttestationIndexOutOfRange)?;
+        self.log(&format!("Revoking attestation {index} for {package_name}..."));
+        user_atts.remove(index);
+
+        let total = self.author_attestations.get(&author).unwrap_or(0);
+        self.author_attestations.insert(&author, &total.saturating_sub(1));
+
+        let hash = Self::generate_key(author, package_name);
+        let mut at = self.attestations.get(&attestor).unwrap();
+        at.insert(&hash, &user_atts);
+        return Ok(());
+    }
+
+    // Issue a liveness challenge to an attestor of a package by recording a fresh nonce
+    // The attestor proves control of their key by signing it with respond_challenge
+    #[payable]
+    #[handle_result]
+    pub fn challenge_attestor(
+        &mut self,
+        // The account that made attestations against the package
+        attestor: AccountId,
+        // The author for a particular package
+        author: AccountId,
+        // The package name that the attestor has made a claim against
+        package_name: String
+    ) -> Result<(), RegistryError> {
+        self.assert_not_paused()?;
+
+        let manifests = self.get_releases(&author)?;
+        self.get_attestation_bucket(manifests, attestor.clone(), author.clone(), package_name.clone())?;
+
+        let initial_storage = near_sdk::env::storage_usage();
+        let namespace = Self::generate_key(author, package_name);
+        self.challenges.insert(&(attestor, namespace), &near_sdk::env::random_seed());
+        return Self::charge_storage(initial_storage);
+    }
+
+    // Answer a pending challenge with an ed25519 signature over the nonce from the signer's access key
+    // Every attestation the signer made with that key on the package is marked as reconfirmed
+    #[handle_result]
+    pub fn respond_challenge(
+        &mut self,
+        // The package name that the attestor has made a claim against
+        package_name: String,
+        // The author for a particular package
+        author: AccountId,
+        // A 64 byte ed25519 signature over the challenge nonce
+        signature: Vec<u8>
+    ) -> Result<(), RegistryError> {
+        self.assert_not_paused()?;
+
+        let attestor = near_sdk::env::signer_account_id();
+        let namespace = Self::generate_key(author.clone(), package_name.clone());
+        let key = (attestor.clone(), namespace.clone());
+
+        let nonce = self.challenges.get(&key).ok_or(RegistryError::NoPendingChallenge)?;
+
+        let pubkey = near_sdk::env::signer_account_pk();
+        ensure(Self::verify_signature(&pubkey, &signature, &nonce), RegistryError::InvalidChallengeSignature)?;
+
+        let manifests = self.get_releases(&author)?;
+        let mut user_atts = self.get_attestation_bucket(manifests, attestor.clone(), author, package_name)?;
+        let now = near_sdk::env::block_timestamp();
+
+        for attestation in user_atts.iter_mut().filter(|a| a.pubkey.as_ref() == Some(&pubkey)) {
+            attestation.reconfirmed_at_ns = now;
+        }
+
+        let mut at = self.attestations.get(&attestor).unwrap();
+        at.insert(&namespace, &user_atts);
+        self.challenges.remove(&key);
+        return Ok(());
+    }
+
+    // Allow or forbid the author of a package from attesting to it, self-attestation is allowed by default
+    // wherever the registry owner has enabled it with set_self_attestation_allowed
+    #[payable]
+    #[handle_result]
+    pub fn set_allow_self_attestation(
+        &mut self,
+        // A string representing the name of a particular package owned by the signer
+        package_name: String,
+        // Whether the signer may attest to their own package
+        allow: bool
+    ) -> Result<(), RegistryError> {
+        self.assert_not_paused()?;
+
+        let initial_storage = near_sdk::env::storage_usage();
+        let author = near_sdk::env::signer_account_id();
+        let manifests = self.get_releases(&author)?;
+        ensure(manifests.contains_key(&package_name), RegistryError::PackageNotFound)?;
+
+        let hash = Self::generate_key(author, package_name);
+        self.allow_self_attestation.insert(&hash, &allow);
+        return Self::charge_storage(initial_storage);
+    }
+
+    // Retrieve all of the attestations for a given package and signer account ID
+    // Returns a list of attestation objects if they exist in the registry, otherwise None
+    // This is a read-only view method and can be queried without a transaction
+    pub fn get_attestations(
+        &self,
+        // The author of the attestation
+        attestor: AccountId,
+        // The package name that the attestor has made a claim against
+        package_name: String,
+        // The author for a particular package
+        author: AccountId
+    ) -> Option<Attestations> {
+        return self.find_attestations(attestor, author, package_name);
+    }
+
+    // Gathers the attestations anyone made against a page of an author's packages, in the order of get_packages
+    // Within a package attestations are grouped by attestor in order of their first attestation like get_all_attestations
+    pub fn get_author_attestations(
+        &self,
+        // An account ID of the author who published the packages
+        author: AccountId,
+        // The index of the first package whose attestations are returned
+        from_index: u64,
+        // The maximum number of packages whose attestations are returned
+        limit: u64
+    ) -> Vec<(PackageName, Attestation)> {
+        return self.get_packages_paged(author.clone(), from_index, limit)
+            .into_iter()
+            .flat_map(|package_name| {
+                return self.get_all_attestations(author.clone(), package_name.clone())
+                    .into_iter()
+                    .map(move |attestation| (package_name.clone(), attestation));
+            })
+            .collect();
+    }
+
+    // The number of attestations made against every package of an author, zero if there are none
+    pub fn total_attestations_for_author(
+        &self,
+        // An account ID of the author who published the packages
+        author: AccountId
+    ) -> u64 {
+        return self.author_attestations.get(&author).unwrap_or(0);
+    }
+
+    // Lists every distinct account that has attested to a package, in order of their first attestation
+    // Attestors stay listed after revoking their attestations
+    pub fn get_attestors(
+        &self,
+        // The author for a particular package
+        author: AccountId,
+        // A string representing the name of a particular package
+        package_name: String
+    ) -> Vec<AccountId> {
+        let namespace = Self::generate_key(author, package_name);
+        return match self.package_attestors.get(&namespace) {
+            Some(attestors) => attestors.to_vec(),
+            None => Vec::new(),
+        };
+    }
+
+    // Gathers the attestations every attestor made against a package, grouped by attestor in order of their first attestation
+    // Empty when nobody has attested, use get_all_attestations_paged for packages with many attestors
+    pub fn get_all_attestations(
+        &self,
+        // The author for a particular package
+        author: AccountId,
+        // A string representing the name of a particular package
+        package_name: String
+    ) -> Attestations {
+        return self.get_all_attestations_paged(author, package_name, 0, u64::MAX);
+    }
+
+    // Gathers the attestations of a page of a package's attestors, pages past the end of the attestors are empty
+    pub fn get_all_attestations_paged(
+        &self,
+        // The author for a particular package
+        author: AccountId,
+        // A string representing the name of a particular package
+        package_name: String,
+        // The index of the first attestor whose attestations are returned
+        from_index: u64,
+        // The maximum number of attestors whose attestations are returned
+        limit: u64
+    ) -> Attestations {
+        let namespace = Self::generate_key(author, package_name);
+        let attestors = match self.package_attestors.get(&namespace) {
+            Some(attestors) => attestors,
+            None => return Vec::new(),
+        };
+
+        let start = from_index.min(attestors.len());
+        let end = from_index.saturating_add(limit).min(attestors.len());
+
+        return (start..end)
+            .filter_map(|index| self.attestations.get(&attestors.get(index).unwrap()))
+            .flat_map(|at| at.get(&namespace).unwrap_or_default())
+            .collect();
+    }
+
+    // Count the attestations an attestor made against a package without returning them
+    // Returns None like get_attestations when the package or attestor is not found
+    pub fn count_attestations(
+        &self,
+        // The author of the attestation
+        attestor: AccountId,
+        // The package name that the attestor has made a claim against
+        package_name: String,
+        // The author for a particular package
+        author: AccountId
+    ) -> Option<u64> {
+        return self.find_attestations(attestor, author, package_name).map(|atts| atts.len() as u64);
+    }
+
+    // Retrieve only the attestations an attestor made against a package with one particular access key
+    // Useful after a key rotation to tell the claims of the old and new keys apart
+    // Returns None like get_attestations when the package or attestor is not found
+    pub fn get_attestations_by_pubkey(
+        &self,
+        // The author of the attestation
+        attestor: AccountId,
+        // The package name that the attestor has made a claim against
+        package_name: String,
+        // The author for a particular package
+        author: AccountId,
+        // The access key the attestations must have been made with
+        pubkey: PublicKey
+    ) -> Option<Attestations> {
+        let attestations = self.find_attestations(attestor, author, package_name)?;
+        return Some(attestations.into_iter().filter(|a| a.pubkey.as_ref() == Some(&pubkey)).collect());
+    }
+
+    // Retrieve the attestations an attestor made against a package that have not expired
+    // Returns None like get_attestations when the package or attestor is not found
+    pub fn get_valid_attestations(
+        &self,
+        // The author of the attestation
+        attestor: AccountId,
+        // The package name that the attestor has made a claim against
+        package_name: String,
+        // The author for a particular package
+        author: AccountId
+    ) -> Option<Attestations> {
+        let now = near_sdk::env::block_timestamp();
+        let attestations = self.get_attestations(attestor, package_name, author)?;
+
+        return Some(attestations.into_iter().filter(|a| a.expires_at == 0 || a.expires_at >= now).collect());
+    }
+
+    // Whether an attestor has attested to a package with a particular access key
+    // Never reverts, an unknown attestor or package simply has not attested
+    pub fn has_attested(
+        &self,
+        // The author of the attestation
+        attestor: AccountId,
+        // The package name that the attestor may have made a claim against
+        package_name: String,
+        // The author for a particular package
+        author: AccountId,
+        // The access key the attestation must have been made with
+        pubkey: PublicKey
+    ) -> bool {
+        let namespace = Self::generate_key(author, package_name);
+
+        return self.attestations.get(&attestor)
+            .and_then(|at| at.get(&namespace))
+            .map_or(false, |atts| atts.iter().any(|a| a.pubkey.as_ref() == Some(&pubkey)));
+    }
+
+    // Retrieve a page of the attestations for a given package and attestor
+    // Pages past the end of the list are empty and limits running past the end are clamped
+    // Returns None like get_attestations when the package or attestor is not found
+    pub fn get_attestations_paged(
+        &self,
+        // The author of the attestation
+        attestor: AccountId,
+        // The package name that the attestor has made a claim against
+        package_name: String,
+        // The author for a particular package
+        author: AccountId,
+        // The index of the first attestation to return
+        from_index: u64,
+        // The maximum number of attestations to return
+        limit: u64
+    ) -> Option<Attestations> {
+        let attestations = self.find_attestations(attestor, author, package_name)?;
+
+        let len = attestations.len() as u64;
+        let start = from_index.min(len);
+        let end = from_index.saturating_add(limit).min(len);
+
+        return Some(attestations[start as usize..end as usize].to_vec());
+    }
+
+    // Retrieve a single attestation at a particular index
+    // Returns None when the package or attestor is not found or the index is out of range
+    // This is a read-only view method and can be queried without a transaction
+    pub fn get_attestation(
+        &self,
+        // The author of the attestation
+        attestor: AccountId,
+        // The package name that the attestor has made a claim against
+        package_name: String,
+        // The author for a particular package
+        author: AccountId,
+        // An index containing an attestation object
+        index: usize
+    ) -> Option<Attestation> {
+        return self.get_attestations(attestor, package_name, author)?.get(index).cloned();
+    }
+
+    #[payable]
+    #[handle_result]
+    pub fn attest_to_type(
+        &mut self,
+        // An account ID of the author who published the manifest
+        author: AccountId,
+        // Name of a type in a package
+        type_name: String,
+    ) -> Result<(), RegistryError> {
+        self.assert_not_paused()?;
+
+        let initial_storage = near_sdk::env::storage_usage();
+        if self.attestations.contains_key(&author) {
+            let count: u8 = match self.type_list.get(&type_name) {
+                Some(v) => v + 1u8,
+                None => 1u8,
+            };
+
+            self.type_list.insert(&type_name, &count);
+        }
+
+        return Self::charge_storage(initial_storage);
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use near_sdk::test_utils::{get_created_receipts, get_logs, VMContextBuilder};
+    use near_sdk::{testing_env, VMContext};
+
+    // Comfortably covers the storage of any single write in these tests, the excess is refunded
+    const STORAGE_DEPOSIT: NearToken = NearToken::from_near(1);
+
+    // Registry whose owner has opted into self-attestation, for tests where bob_near attests to his own packages
+    fn self_attesting_contract() -> Contract {
+        let mut contract = Contract::default();
+        contract.config.self_attestation_allowed = true;
+        return contract;
+    }
+
+    fn get_context(is_view: bool) -> VMContext {
+        VMContextBuilder::new()
+            .signer_account_id("bob_near".parse().unwrap())
+            .is_view(is_view)
+            .attached_deposit(STORAGE_DEPOSIT)
+            .build()
+    }
+
+    #[test]
+    fn set_package_manifest() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+        let cid = "QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB".to_string();
+        let name = "test-package".to_string();
+        let version = "0.0.1".to_string();
+        let content_type = "ipfs".to_string();
+
+        let mut contract = Contract::default();
+        contract.create_manifest(
+            name.clone(),
+            version.clone(),
+            content_type.clone(),
+            cid.clone(),
+            Vec::new(),
+            None,
+            false,
+            None,
+            Vec::new()
+        ).unwrap();
+        assert_eq!(
+            contract.get_manifest(context.signer_account_id.clone(), name.clone(), version.clone()).unwrap(),
+            cid.clone()
+        );
+    }
+
+    #[test]
+    fn set_multiple_manifest() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+        let cid = "QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB".to_string();
+        let name = "test-package".to_string();
+        let version = "0.0.1".to_string();
+        let content_type = "ipfs".to_string();
+
+        let mut contract = Contract::default();
+        contract.create_manifest(
+            name.clone(),
+            version.clone(),
+            content_type.clone(),
+            cid.clone(),
+            Vec::new(),
+            None,
+            false,
+            None,
+            Vec::new()
+        ).unwrap();
+
+        contract.create_manifest(
+            "new_package".to_string(),
+            version.clone(),
+            content_type.clone(),
+            cid.clone(),
+            Vec::new(),
+            None,
+            false,
+            None,
+            Vec::new()
+        ).unwrap();
+
+        contract.create_manifest(
+            name.clone(),
+            "0.0.2".to_string(),
+            content_type.clone(),
+            cid.clone(),
+            Vec::new(),
+            None,
+            false,
+            None,
+            Vec::new()
+        ).unwrap();
+
+        assert_eq!(
+            contract.get_manifest(context.signer_account_id.clone(), name.clone(), version.clone()).unwrap(),
+            cid.clone()
+        );
+
+        assert_eq!(
+            contract.get_manifest(context.signer_account_id.clone(), name.clone(), "0.0.2".to_string()).unwrap(),
+            cid.clone()
+        );
+
+        assert_eq!(
+            contract.get_manifest(context.signer_account_id.clone(), "new_package".to_string(), version.to_string()).unwrap(),
+            cid.clone()
+        );
+    }
+
+    #[test]
+    fn update_existing_manifest() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+        let cid = "QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB".to_string();
+        let name = "test-package".to_string();
+        let version = "0.0.1".to_string();
+        let content_type = "ipfs".to_string();
+
+        let mut contract = Contract::default();
+        contract.create_manifest(
+            name.clone(),
+            version.clone(),
+            content_type.clone(),
+            cid.clone(),
+            Vec::new(),
+            None,
+            false,
+            None,
+            Vec::new()
+        ).unwrap();
+
+        let new_cid = "QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n".to_string();
+
+        contract.update_manifest(
+            name.to_string(),
+            version.to_string(),
+            "hyperfiles".to_string(),
+            new_cid.clone()
+        ).unwrap();
+
+        assert_eq!(
+            contract.get_manifest(context.signer_account_id.clone(), name.clone(), version.to_string()).unwrap(),
+            new_cid.clone()
+        );
+    }
+
+
+    #[test]
+    fn set_attestation() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+        let cid = "QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB".to_string();
+        let name = "test-package".to_string();
+        let version = "0.0.1".to_string();
+        let content_type = "ipfs".to_string();
+
+        let mut contract = self_attesting_contract();
+        contract.create_manifest(
+            name.clone(),
+            version.clone(),
+            content_type.clone(),
+            cid.clone(),
+            Vec::new(),
+            None,
+            false,
+            None,
+            Vec::new()
+        ).unwrap();
+
+
+        let attestation = "QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n".to_string();
+
+        contract.create_attestation(name.clone(), context.signer_account_id.clone(), attestation.clone(), None, None, false, None, String::new(), String::new(), 0).unwrap();
+
+        assert_eq!(
+            contract.get_attestation(context.signer_account_id.clone(), name.clone(), context.signer_account_id.clone(), 0).unwrap().cid,
+            attestation.clone()
+        );
+    }
+
+    #[test]
+    fn get_versions_in_range() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+        let cid = "QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB".to_string();
+        let name = "test-package".to_string();
+        let content_type = "ipfs".to_string();
+
+        let mut contract = Contract::default();
+        for version in ["1.5.0", "1.0.0", "1.2.0", "1.3.0-beta.1", "1.6.0", "1.4.2"] {
+            contract.create_manifest(
+                name.clone(),
+                version.to_string(),
+                content_type.clone(),
+                cid.clone(),
+                Vec::new(),
+                None,
+                false,
+                None,
+                Vec::new()
+            ).unwrap();
+        }
+
+        let versions = contract.get_versions_between(
+            context.signer_account_id.clone(),
+            name.clone(),
+            "1.2.0".to_string(),
+            "1.5.0".to_string()
+        ).unwrap().into_iter().map(|m| m.version).collect::<Vec<String>>();
+
+        assert_eq!(versions, vec!["1.2.0", "1.3.0-beta.1", "1.4.2", "1.5.0"]);
+    }
+
+    #[test]
+    fn get_versions_in_inverted_range() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+        let cid = "QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB".to_string();
+        let name = "test-package".to_string();
+        let content_type = "ipfs".to_string();
+
+        let mut contract = Contract::default();
+        for version in ["1.2.0", "1.5.0"] {
+            contract.create_manifest(
+                name.clone(),
+                version.to_string(),
+                content_type.clone(),
+                cid.clone(),
+                Vec::new(),
+                None,
+                false,
+                None,
+                Vec::new()
+            ).unwrap();
+        }
+
+        assert!(contract.get_versions_between(
+            context.signer_account_id.clone(),
+            name.clone(),
+            "1.5.0".to_string(),
+            "1.2.0".to_string()
+        ).unwrap().is_empty());
+    }
+
+    #[test]
+    fn set_attestation_with_note() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+        let cid = "QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB".to_string();
+        let name = "test-package".to_string();
+
+        let mut contract = self_attesting_contract();
+        contract.create_manifest(
+            name.clone(),
+            "0.0.1".to_string(),
+            "ipfs".to_string(),
+            cid.clone(),
+            Vec::new(),
+            None,
+            false,
+            None,
+            Vec::new()
+        ).unwrap();
+
+        let attestation = "QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n".to_string();
+        let note = "audited commit abc, no criticals".to_string();
+
+        contract.create_attestation(name.clone(), context.signer_account_id.clone(), attestation.clone(), Some(note.clone()), None, false, None, String::new(), String::new(), 0).unwrap();
+
+        assert_eq!(
+            contract.get_attestation(context.signer_account_id.clone(), name.clone(), context.signer_account_id.clone(), 0).unwrap().note,
+            Some(note)
+        );
+    }
+
+    #[test]
+    fn reject_oversized_attestation_note() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+        let name = "test-package".to_string();
+
+        let mut contract = self_attesting_contract();
+        contract.create_manifest(
+            name.clone(),
+            "0.0.1".to_string(),
+            "ipfs".to_string(),
+            "QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB".to_string(),
+            Vec::new(),
+            None,
+            false,
+            None,
+            Vec::new()
+        ).unwrap();
+
+        let result = contract.create_attestation(
+            name.clone(),
+            context.signer_account_id.clone(),
+            "QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n".to_string(),
+            Some("a".repeat(MAX_NOTE_LENGTH + 1)),
+            None,
+            false,
+            None,
+            String::new(),
+            String::new(),
+            0
+        );
+        assert_eq!(result, Err(RegistryError::NoteTooLong));
+    }
+
+    #[test]
+    fn detect_seeded_duplicate_versions() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+        let cid = "QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB".to_string();
+        let name = "test-package".to_string();
+
+        let mut contract = Contract::default();
+        for version in ["0.0.1", "0.0.2"] {
+            contract.create_manifest(
+                name.clone(),
+                version.to_string(),
+                "ipfs".to_string(),
+                cid.clone(),
+                Vec::new(),
+                None,
+                false,
+                None,
+                Vec::new()
+            ).unwrap();
+        }
+
+        // Seed a duplicate version directly into storage the way legacy data would look
+        let mut manifests = contract.packages.get(&context.signer_account_id).unwrap();
+        let mut versions = manifests.get(&name).unwrap();
+        versions.push(versions[0].clone());
+        manifests.insert(&name, &versions);
+
+        assert_eq!(
+            contract.detect_duplicate_versions(context.signer_account_id.clone(), name.clone()).unwrap(),
+            vec!["0.0.1".to_string()]
+        );
+    }
+
+    #[test]
+    fn substitute_default_content_type() {
+        let context = get_owner_context("bob_near");
+        testing_env!(context.clone());
+        let name = "test-package".to_string();
+        let version = "0.0.1".to_string();
+
+        let mut contract = Contract::default();
+        contract.set_default_content_type("hyperfiles".to_string()).unwrap();
+        contract.create_manifest(
+            name.clone(),
+            version.clone(),
+            "".to_string(),
+            "QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB".to_string(),
+            Vec::new(),
+            None,
+            false,
+            None,
+            Vec::new()
+        ).unwrap();
+
+        let manifests = contract.get_versions_between(
+            context.signer_account_id.clone(),
+            name.clone(),
+            version.clone(),
+            version.clone()
+        ).unwrap();
+
+        assert_eq!(manifests[0].content_type, "hyperfiles".to_string());
+    }
+
+    #[test]
+    fn reject_missing_content_type_without_default() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+
+        let mut contract = Contract::default();
+        let result = contract.create_manifest(
+            "test-package".to_string(),
+            "0.0.1".to_string(),
+            "".to_string(),
+            "QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB".to_string(),
+            Vec::new(),
+            None,
+            false,
+            None,
+            Vec::new()
+        );
+        assert_eq!(result, Err(RegistryError::MissingContentType));
+    }
+
+    #[test]
+    fn allow_self_attestation_by_default() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+        let name = "test-package".to_string();
+        let attestation = "QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n".to_string();
+
+        let mut contract = self_attesting_contract();
+        contract.create_manifest(
+            name.clone(),
+            "0.0.1".to_string(),
+            "ipfs".to_string(),
+            "QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB".to_string(),
+            Vec::new(),
+            None,
+            false,
+            None,
+            Vec::new()
+        ).unwrap();
+
+        contract.create_attestation(name.clone(), context.signer_account_id.clone(), attestation.clone(), None, None, false, None, String::new(), String::new(), 0).unwrap();
+
+        assert_eq!(
+            contract.get_attestation(context.signer_account_id.clone(), name.clone(), context.signer_account_id.clone(), 0).unwrap().cid,
+            attestation
+        );
+    }
+
+    #[test]
+    fn reject_self_attestation_when_forbidden() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+        let name = "test-package".to_string();
+
+        let mut contract = self_attesting_contract();
+        contract.create_manifest(
+            name.clone(),
+            "0.0.1".to_string(),
+            "ipfs".to_string(),
+            "QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB".to_string(),
+            Vec::new(),
+            None,
+            false,
+            None,
+            Vec::new()
+        ).unwrap();
+
+        contract.set_allow_self_attestation(name.clone(), false).unwrap();
+        let result = contract.create_attestation(
+            name.clone(),
+            context.signer_account_id.clone(),
+            "QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n".to_string(),
+            None,
+            None,
+            false,
+            None,
+            String::new(),
+            String::new(),
+            0
+        );
+        assert_eq!(result, Err(RegistryError::SelfAttestation));
+    }
+
+    #[test]
+    fn larger_cid_costs_more_to_publish() {
+        let context = get_context(true);
+        testing_env!(context);
+        let contract = Contract::default();
+
+        let small = contract.estimate_publish_cost(
+            "test-package".to_string(),
+            "0.0.1".to_string(),
+            "ipfs".to_string(),
+            "QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB".to_string(),
+            Vec::new(),
+            None,
+            Vec::new()
+        );
+
+        let large = contract.estimate_publish_cost(
+            "test-package".to_string(),
+            "0.0.1".to_string(),
+            "ipfs".to_string(),
+            "bafybeicn7i3soqdgr7dwnrwytgq4zxy7a5jpkizrvhm5mv6bgjd32wm3q4".to_string(),
+            Vec::new(),
+            None,
+            Vec::new()
+        );
+
+        assert!(large.0 > small.0);
+    }
+
+    #[test]
+    fn publish_with_exactly_the_estimate() {
+        let name = "test-package".to_string();
+        let cid = "QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB".to_string();
+        testing_env!(get_context(false));
+        let mut contract = Contract::default();
+        let estimate = contract.estimate_publish_cost(
+            name.clone(),
+            "0.0.1".to_string(),
+            "ipfs".to_string(),
+            cid.clone(),
+            Vec::new(),
+            None,
+            Vec::new()
+        );
+
+        testing_env!(VMContextBuilder::new()
+            .signer_account_id("bob_near".parse().unwrap())
+            .attached_deposit(NearToken::from_yoctonear(estimate.0))
+            .build());
+        let result = contract.create_manifest(
+            name,
+            "0.0.1".to_string(),
+            "ipfs".to_string(),
+            cid,
+            Vec::new(),
+            None,
+            false,
+            None,
+            Vec::new()
+        );
+        assert_eq!(result, Ok(()));
+    }
+
+    #[test]
+    fn publish_with_metadata_with_exactly_the_estimate() {
+        let name = "test-package".to_string();
+        let cid = "QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB".to_string();
+        let types = vec!["Token".to_string(), "Balance".to_string()];
+        let encryption = EncryptionInfo { scheme: "aes-256-gcm".to_string(), key_ref: "kms://registry/key".to_string() };
+        let dependencies: Vec<Dependency> = vec![
+            ("alice_near".parse().unwrap(), "base-package".to_string(), "^1.0.0".to_string())
+        ];
+        testing_env!(get_context(false));
+        let mut contract = Contract::default();
+
+        let bare = contract.estimate_publish_cost(
+            name.clone(),
+            "0.0.1".to_string(),
+            "ipfs".to_string(),
+            cid.clone(),
+            Vec::new(),
+            None,
+            Vec::new()
+        );
+        let estimate = contract.estimate_publish_cost(
+            name.clone(),
+            "0.0.1".to_string(),
+            "ipfs".to_string(),
+            cid.clone(),
+            types.clone(),
+            Some(encryption.clone()),
+            dependencies.clone()
+        );
+        assert!(estimate.0 > bare.0);
+
+        testing_env!(VMContextBuilder::new()
+            .signer_account_id("bob_near".parse().unwrap())
+            .attached_deposit(NearToken::from_yoctonear(estimate.0))
+            .build());
+        let result = contract.create_manifest(
+            name,
+            "0.0.1".to_string(),
+            "ipfs".to_string(),
+            cid,
+            types,
+            Some(encryption),
+            false,
+            None,
+            dependencies
+        );
+        assert_eq!(result, Ok(()));
+    }
+
+    #[test]
+    fn detect_cid_shared_across_versions() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+        let cid = "QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB".to_string();
+        let name = "test-package".to_string();
+
+        let mut contract = Contract::default();
+        for (version, cid) in [
+            ("0.0.1", cid.clone()),
+            ("0.0.2", "QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n".to_string()),
+            ("0.0.3", cid.clone())
+        ] {
+            contract.create_manifest(
+                name.clone(),
+                version.to_string(),
+                "ipfs".to_string(),
+                cid,
+                Vec::new(),
+                None,
+                false,
+                None,
+                Vec::new()
+            ).unwrap();
+        }
+
+        assert_eq!(
+            contract.intra_package_cid_duplicates(context.signer_account_id.clone(), name.clone()).unwrap(),
+            vec![(cid.clone(), vec!["0.0.1".to_string(), "0.0.3".to_string()])]
+        );
+    }
+
+    #[test]
+    fn package_root_changes_on_publish() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+        let cid = "QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB".to_string();
+        let name = "test-package".to_string();
+
+        let mut contract = Contract::default();
+        contract.create_manifest(
+            name.clone(),
+            "0.0.1".to_string(),
+            "ipfs".to_string(),
+            cid.clone(),
+            Vec::new(),
+            None,
+            false,
+            None,
+            Vec::new()
+        ).unwrap();
+
+        let first = contract.get_package_root(context.signer_account_id.clone(), name.clone()).unwrap();
+        let manifest = contract.get_versions_between(
+            context.signer_account_id.clone(),
+            name.clone(),
+            "0.0.1".to_string(),
+            "0.0.1".to_string()
+        ).unwrap()[0].clone();
+
+        // A single manifest is its own root
+        assert_eq!(
+            first,
+            merkle::to_hex(&merkle::hash_leaf(&near_sdk::borsh::to_vec(&manifest).unwrap()))
+        );
+
+        contract.create_manifest(
+            name.clone(),
+            "0.0.2".to_string(),
+            "ipfs".to_string(),
+            cid.clone(),
+            Vec::new(),
+            None,
+            false,
+            None,
+            Vec::new()
+        ).unwrap();
+
+        assert_ne!(contract.get_package_root(context.signer_account_id.clone(), name.clone()).unwrap(), first);
+    }
+
+    fn from_hex(hex: &str) -> Vec<u8> {
+        return (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect();
+    }
+
+    // Mirrors the off-chain verification a client performs against get_package_root
+    fn verify_manifest_proof(leaf: &str, proof: &MerkleProof, root: &str) -> bool {
+        let mut node = from_hex(leaf);
+        for step in &proof.steps {
+            node = if step.is_left {
+                merkle::hash_pair(&from_hex(&step.hash), &node)
+            } else {
+                merkle::hash_pair(&node, &from_hex(&step.hash))
+            };
+        }
+
+        return node == from_hex(root);
+    }
+
+    #[test]
+    fn manifest_proof_verifies_against_root() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+        let name = "test-package".to_string();
+
+        let mut contract = Contract::default();
+        for (version, cid) in [
+            ("0.0.1", "QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB"),
+            ("0.0.2", "QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n"),
+            ("0.0.3", "bafybeicn7i3soqdgr7dwnrwytgq4zxy7a5jpkizrvhm5mv6bgjd32wm3q4")
+        ] {
+            contract.create_manifest(
+                name.clone(),
+                version.to_string(),
+                "ipfs".to_string(),
+                cid.to_string(),
+                Vec::new(),
+                None,
+                false,
+                None,
+                Vec::new()
+            ).unwrap();
+        }
+
+        let root = contract.get_package_root(context.signer_account_id.clone(), name.clone()).unwrap();
+        for version in ["0.0.1", "0.0.2", "0.0.3"] {
+            let proof = contract.get_manifest_proof(
+                context.signer_account_id.clone(),
+                name.clone(),
+                version.to_string()
+            ).unwrap();
+
+            assert!(verify_manifest_proof(&proof.leaf, &proof, &root));
+        }
+
+        let proof = contract.get_manifest_proof(
+            context.signer_account_id.clone(),
+            name.clone(),
+            "0.0.2".to_string()
+        ).unwrap();
+
+        let tampered_leaf = merkle::to_hex(&merkle::hash_leaf(b"tampered manifest"));
+
+        assert!(!verify_manifest_proof(&tampered_leaf, &proof, &root));
+        assert!(contract.get_manifest_proof(
+            context.signer_account_id.clone(),
+            name.clone(),
+            "9.9.9".to_string()
+        ).is_none());
+    }
+
+    // Checks the incrementally maintained root against a rebuild from every version and proves each version
+    fn assert_root_matches_rebuild(contract: &mut Contract, author: &AccountId, name: &str) {
+        let root = contract.get_package_root(author.clone(), name.to_string()).unwrap();
+        let versions = contract.find_versions(author, &name.to_string()).unwrap();
+
+        for manifest in &versions {
+            let proof = contract.get_manifest_proof(author.clone(), name.to_string(), manifest.version.clone()).unwrap();
+            assert!(verify_manifest_proof(&proof.leaf, &proof, &root));
+        }
+
+        let namespace = Contract::generate_key(author.clone(), name.to_string());
+        contract.refresh_package_root(&namespace, &versions);
+        assert_eq!(contract.get_package_root(author.clone(), name.to_string()).unwrap(), root);
+    }
+
+    #[test]
+    fn incremental_package_root_matches_rebuild() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+        let author = context.signer_account_id.clone();
+        let name = "test-package".to_string();
+
+        let mut contract = Contract::default();
+        for version in ["0.0.1", "0.0.2", "0.0.3", "0.0.4", "0.0.5"] {
+            let before = contract.get_package_root(author.clone(), name.clone());
+            publish_versions(&mut contract, &name, &[version]);
+            assert_ne!(contract.get_package_root(author.clone(), name.clone()), before);
+            assert_root_matches_rebuild(&mut contract, &author, &name);
+        }
+
+        contract.batch_create_manifests(
+            name.clone(),
+            vec![
+                ("0.0.6".to_string(), "ipfs".to_string(), "QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB".to_string()),
+                ("0.0.7".to_string(), "ipfs".to_string(), "QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n".to_string())
+            ],
+            false
+        ).unwrap();
+        assert_root_matches_rebuild(&mut contract, &author, &name);
+
+        contract.update_manifest(
+            name.clone(),
+            "0.0.3".to_string(),
+            "ipfs".to_string(),
+            "QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n".to_string()
+        ).unwrap();
+        assert_root_matches_rebuild(&mut contract, &author, &name);
+
+        contract.set_yanked(name.clone(), "0.0.2".to_string(), true).unwrap();
+        assert_root_matches_rebuild(&mut contract, &author, &name);
+
+        // Removing the last version drops its path, removing an earlier one rebuilds the tree
+        contract.delete_manifest(name.clone(), "0.0.7".to_string()).unwrap();
+        assert_root_matches_rebuild(&mut contract, &author, &name);
+        contract.undo_last_publish(name.clone()).unwrap();
+        assert_root_matches_rebuild(&mut contract, &author, &name);
+        contract.delete_manifest(name.clone(), "0.0.1".to_string()).unwrap();
+        assert_root_matches_rebuild(&mut contract, &author, &name);
+    }
+
+    #[test]
+    fn merkle_hashes_are_domain_separated() {
+        testing_env!(get_context(false));
+        let left = merkle::hash_leaf(b"left manifest");
+        let right = merkle::hash_leaf(b"right manifest");
+        let mut children = left.clone();
+        children.extend_from_slice(&right);
+
+        assert_ne!(merkle::hash_leaf(b"left manifest"), near_sdk::env::sha256(b"left manifest"));
+        // An interior node cannot be passed off as a leaf over its concatenated children
+        assert_ne!(merkle::hash_pair(&left, &right), merkle::hash_leaf(&children));
+        assert_ne!(merkle::hash_pair(&left, &right), near_sdk::env::sha256(&children));
+    }
+
+    fn get_contract_context(signer: &str) -> VMContext {
+        VMContextBuilder::new()
+            .signer_account_id(signer.parse().unwrap())
+            .predecessor_account_id("org_near".parse().unwrap())
+            .attached_deposit(STORAGE_DEPOSIT)
+            .build()
+    }
+
+    // Registry owned by owner_near with org_near registered as a contract publisher
+    fn setup_contract_publisher() -> Contract {
+        testing_env!(get_owner_context("owner_near"));
+        let mut contract = Contract::new("owner_near".parse().unwrap());
+        contract.register_contract_publisher("org_near".parse().unwrap());
+        return contract;
+    }
+
+    #[test]
+    fn publish_as_allowed_delegate() {
+        let mut contract = setup_contract_publisher();
+        testing_env!(get_contract_context("bob_near"));
+        let name = "test-package".to_string();
+        let cid = "QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB".to_string();
+
+        contract.set_publish_allowlist(vec!["bob_near".parse().unwrap()]).unwrap();
+        contract.create_manifest(
+            name.clone(),
+            "0.0.1".to_string(),
+            "ipfs".to_string(),
+            cid.clone(),
+            Vec::new(),
+            None,
+            true,
+            None,
+            Vec::new()
+        ).unwrap();
+
+        assert_eq!(contract.get_latest_manifest("org_near".parse().unwrap(), name.clone()).unwrap(), cid);
+    }
+
+    #[test]
+    fn contract_author_sets_default_content_type() {
+        let mut contract = setup_contract_publisher();
+        testing_env!(get_contract_context("bob_near"));
+        let name = "test-package".to_string();
+
+        contract.set_publish_allowlist(vec!["bob_near".parse().unwrap()]).unwrap();
+        contract.set_default_content_type("json".to_string()).unwrap();
+        contract.create_manifest(
+            name.clone(),
+            "0.0.1".to_string(),
+            "".to_string(),
+            "QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB".to_string(),
+            Vec::new(),
+            None,
+            true,
+            None,
+            Vec::new()
+        ).unwrap();
+
+        let manifest = contract.get_latest_manifest_full("org_near".parse().unwrap(), name).unwrap();
+        assert_eq!(manifest.content_type, "json".to_string());
+    }
+
+    #[test]
+    fn contract_author_sets_versioning_scheme() {
+        let mut contract = setup_contract_publisher();
+        testing_env!(get_contract_context("bob_near"));
+
+        contract.set_publish_allowlist(vec!["bob_near".parse().unwrap()]).unwrap();
+        contract.set_versioning_scheme("test-package".to_string(), VersioningScheme::Semver, true).unwrap();
+        let result = contract.create_manifest(
+            "test-package".to_string(),
+            "1.0".to_string(),
+            "ipfs".to_string(),
+            "QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB".to_string(),
+            Vec::new(),
+            None,
+            true,
+            None,
+            Vec::new()
+        );
+        assert_eq!(result, Err(RegistryError::InvalidVersion));
+    }
+
+    #[test]
+    fn reject_publish_from_unlisted_delegate() {
+        let mut contract = setup_contract_publisher();
+        testing_env!(get_contract_context("alice_near"));
+
+        contract.set_publish_allowlist(vec!["bob_near".parse().unwrap()]).unwrap();
+        let result = contract.create_manifest(
+            "test-package".to_string(),
+            "0.0.1".to_string(),
+            "ipfs".to_string(),
+            "QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB".to_string(),
+            Vec::new(),
+            None,
+            true,
+            None,
+            Vec::new()
+        );
+        assert_eq!(result, Err(RegistryError::NotAllowedToPublish));
+    }
+
+    #[test]
+    fn latest_manifest_opt_for_missing_package() {
+        let context = get_context(true);
+        testing_env!(context.clone());
+        let contract = Contract::default();
+
+        assert_eq!(contract.get_latest_manifest_opt(context.signer_account_id.clone(), "test-package".to_string()), None);
+    }
+
+    #[test]
+    fn latest_manifest_opt_for_empty_versions() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+        let name = "test-package".to_string();
+
+        let mut contract = Contract::default();
+        contract.create_manifest(
+            name.clone(),
+            "0.0.1".to_string(),
+            "ipfs".to_string(),
+            "QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB".to_string(),
+            Vec::new(),
+            None,
+            false,
+            None,
+            Vec::new()
+        ).unwrap();
+
+        // Seed an empty version list directly into storage
+        let mut manifests = contract.packages.get(&context.signer_account_id).unwrap();
+        manifests.insert(&name, &Vec::new());
+
+        assert_eq!(contract.get_latest_manifest_opt(context.signer_account_id.clone(), name.clone()), None);
+    }
+
+    #[test]
+    fn latest_manifest_opt_for_published_package() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+        let name = "test-package".to_string();
+        let cid = "QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n".to_string();
+
+        let mut contract = Contract::default();
+        for (version, cid) in [("0.0.1", "QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB"), ("0.0.2", cid.as_str())] {
+            contract.create_manifest(
+                name.clone(),
+                version.to_string(),
+                "ipfs".to_string(),
+                cid.to_string(),
+                Vec::new(),
+                None,
+                false,
+                None,
+                Vec::new()
+            ).unwrap();
+        }
+
+        assert_eq!(contract.get_latest_manifest_opt(context.signer_account_id.clone(), name.clone()), Some(cid));
+    }
+
+    fn setup_merge(contract: &mut Contract) {
+        for (package_name, version, cid) in [
+            ("from-package", "0.0.1", "QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB"),
+            ("from-package", "0.0.2", "QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n"),
+            ("into-package", "0.0.2", "bafybeicn7i3soqdgr7dwnrwytgq4zxy7a5jpkizrvhm5mv6bgjd32wm3q4"),
+            ("into-package", "0.0.3", "bafybeicn7i3soqdgr7dwnrwytgq4zxy7a5jpkizrvhm5mv6bgjd32wm3q4")
+        ] {
+            contract.create_manifest(
+                package_name.to_string(),
+                version.to_string(),
+                "ipfs".to_string(),
+                cid.to_string(),
+                Vec::new(),
+                None,
+                false,
+                None,
+                Vec::new()
+            ).unwrap();
+        }
+    }
+
+    #[test]
+    fn merge_packages_without_collisions() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+        let author = context.signer_account_id.clone();
+
+        let mut contract = Contract::default();
+        for (package_name, version) in [("from-package", "0.0.1"), ("into-package", "0.0.2")] {
+            contract.create_manifest(
+                package_name.to_string(),
+                version.to_string(),
+                "ipfs".to_string(),
+                "QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB".to_string(),
+                Vec::new(),
+                None,
+                false,
+                None,
+                Vec::new()
+            ).unwrap();
+        }
+
+        contract.merge_packages("from-package".to_string(), "into-package".to_string(), MergeStrategy::Error).unwrap();
+
+        let versions = contract.get_versions_between(author.clone(), "into-package".to_string(), "0.0.0".to_string(), "9.9.9".to_string()).unwrap()
+            .into_iter()
+            .map(|m| m.version)
+            .collect::<Vec<String>>();
+
+        assert_eq!(versions, vec!["0.0.1", "0.0.2"]);
+        assert_eq!(contract.get_latest_manifest_opt(author.clone(), "from-package".to_string()), None);
+    }
+
+    #[test]
+    fn merge_packages_skipping_collisions() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+        let author = context.signer_account_id.clone();
+
+        let mut contract = Contract::default();
+        setup_merge(&mut contract);
+        contract.merge_packages("from-package".to_string(), "into-package".to_string(), MergeStrategy::Skip).unwrap();
+
+        assert_eq!(
+            contract.get_manifest(author.clone(), "into-package".to_string(), "0.0.2".to_string()).unwrap(),
+            "bafybeicn7i3soqdgr7dwnrwytgq4zxy7a5jpkizrvhm5mv6bgjd32wm3q4".to_string()
+        );
+        assert_eq!(
+            contract.get_manifest(author.clone(), "into-package".to_string(), "0.0.1".to_string()).unwrap(),
+            "QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB".to_string()
+        );
+    }
+
+    #[test]
+    fn merge_packages_overwriting_collisions() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+        let author = context.signer_account_id.clone();
+
+        let mut contract = Contract::default();
+        setup_merge(&mut contract);
+        contract.merge_packages("from-package".to_string(), "into-package".to_string(), MergeStrategy::Overwrite).unwrap();
+
+        assert_eq!(
+            contract.get_manifest(author.clone(), "into-package".to_string(), "0.0.2".to_string()).unwrap(),
+            "QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n".to_string()
+        );
+        assert_eq!(contract.get_latest_manifest_opt(author.clone(), "from-package".to_string()), None);
+    }
+
+    #[test]
+    fn merge_packages_erroring_on_collisions() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+
+        let mut contract = Contract::default();
+        setup_merge(&mut contract);
+        let result = contract.merge_packages("from-package".to_string(), "into-package".to_string(), MergeStrategy::Error);
+        assert_eq!(result, Err(RegistryError::MergeConflict));
+    }
+
+    #[test]
+    fn transfer_package_to_new_owner() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+        let author = context.signer_account_id.clone();
+        let new_owner: AccountId = "alice_near".parse().unwrap();
+        let name = "test-package".to_string();
+
+        let mut contract = Contract::default();
+        publish_versions(&mut contract, &name, &["0.0.1", "0.0.2"]);
+        let root = contract.get_package_root(author.clone(), name.clone()).unwrap();
+
+        contract.offer_package_transfer(name.clone(), new_owner.clone()).unwrap();
+        testing_env!(get_owner_context("alice_near"));
+        contract.accept_package_transfer(author.clone(), name.clone()).unwrap();
+
+        assert_eq!(contract.list_versions(new_owner.clone(), name.clone()).unwrap(), vec!["0.0.1", "0.0.2"]);
+        assert_eq!(contract.get_packages(new_owner.clone()), vec![name.clone()]);
+        assert_eq!(contract.get_package_root(new_owner, name.clone()).unwrap(), root);
+        assert_eq!(contract.get_latest_manifest_opt(author.clone(), name), None);
+        assert!(contract.get_packages(author).is_empty());
+    }
+
+    #[test]
+    fn transfer_package_name_collision() {
+        let new_owner = "alice_near";
+        let name = "test-package";
+
+        let mut contract = Contract::default();
+        testing_env!(VMContextBuilder::new()
+            .signer_account_id(new_owner.parse().unwrap())
+            .attached_deposit(STORAGE_DEPOSIT)
+            .build());
+        publish_versions(&mut contract, name, &["1.0.0"]);
+
+        testing_env!(get_context(false));
+        publish_versions(&mut contract, name, &["0.0.1"]);
+        contract.offer_package_transfer(name.to_string(), new_owner.parse().unwrap()).unwrap();
+
+        testing_env!(get_owner_context(new_owner));
+        let result = contract.accept_package_transfer("bob_near".parse().unwrap(), name.to_string());
+        assert_eq!(result, Err(RegistryError::PackageNameTaken));
+    }
+
+    #[test]
+    fn unaccepted_transfer_offer_does_not_move_package() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+        let author = context.signer_account_id.clone();
+        let new_owner: AccountId = "alice_near".parse().unwrap();
+        let name = "test-package".to_string();
+
+        let mut contract = Contract::default();
+        publish_versions(&mut contract, &name, &["0.0.1"]);
+        contract.offer_package_transfer(name.clone(), new_owner.clone()).unwrap();
+
+        assert_eq!(contract.list_versions(author.clone(), name.clone()).unwrap(), vec!["0.0.1"]);
+        assert_eq!(contract.get_packages(author.clone()), vec![name.clone()]);
+        assert!(contract.packages.get(&new_owner).is_none());
+
+        // Only the account the package was offered to may accept it
+        testing_env!(get_owner_context("carol_near"));
+        let result = contract.accept_package_transfer(author.clone(), name.clone());
+        assert_eq!(result, Err(RegistryError::NoPendingTransfer));
+        assert_eq!(contract.list_versions(author, name).unwrap(), vec!["0.0.1"]);
+    }
+
+    // Public key derived from a 32 byte seed of 7s, used to sign the challenge nonce below
+    const CHALLENGE_PK: &str = "ed25519:GmaDrppBC7P5ARKV8g3djiwP89vz1jLK23V2GBjuAEGB";
+    // Signature over a nonce of 32 bytes of 1s
+    const CHALLENGE_SIGNATURE: &str = "013f9d903c1a0a90b0beea2534582e2bb694712503215236622851f5afd54ad5f2a89983965e192edd928d484bdb1e1e521ba704fbe37968371a11597363fb0d";
+
+    fn get_challenge_context() -> VMContext {
+        VMContextBuilder::new()
+            .signer_account_id("bob_near".parse().unwrap())
+            .signer_account_pk(CHALLENGE_PK.parse().unwrap())
+            .random_seed([1u8; 32])
+            .block_timestamp(1_700_000_000_000_000_000)
+            .attached_deposit(STORAGE_DEPOSIT)
+            .build()
+    }
+
+    fn setup_challenge(contract: &mut Contract, author: AccountId, name: String) {
+        contract.create_manifest(
+            name.clone(),
+            "0.0.1".to_string(),
+            "ipfs".to_string(),
+            "QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB".to_string(),
+            Vec::new(),
+            None,
+            false,
+            None,
+            Vec::new()
+        ).unwrap();
+
+        contract.create_attestation(
+            name.clone(),
+            author.clone(),
+            "QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n".to_string(),
+            None,
+            None,
+            false,
+            None,
+            String::new(),
+            String::new(),
+            0
+        ).unwrap();
+
+        contract.challenge_attestor(author.clone(), author, name).unwrap();
+    }
+
+    #[test]
+    fn respond_to_challenge_with_valid_signature() {
+        let context = get_challenge_context();
+        testing_env!(context.clone());
+        let author = context.signer_account_id.clone();
+        let name = "test-package".to_string();
+
+        let mut contract = self_attesting_contract();
+        setup_challenge(&mut contract, author.clone(), name.clone());
+        contract.respond_challenge(name.clone(), author.clone(), from_hex(CHALLENGE_SIGNATURE)).unwrap();
+
+        assert_eq!(
+            contract.get_attestation(author.clone(), name.clone(), author.clone(), 0).unwrap().reconfirmed_at_ns,
+            context.block_timestamp
+        );
+    }
+
+    #[test]
+    fn reject_challenge_response_with_invalid_signature() {
+        let context = get_challenge_context();
+        testing_env!(context.clone());
+        let author = context.signer_account_id.clone();
+        let name = "test-package".to_string();
+
+        let mut contract = self_attesting_contract();
+        setup_challenge(&mut contract, author.clone(), name.clone());
+
+        let mut signature = from_hex(CHALLENGE_SIGNATURE);
+        signature[0] ^= 1;
+        let result = contract.respond_challenge(name, author, signature);
+        assert_eq!(result, Err(RegistryError::InvalidChallengeSignature));
+    }
+
+    #[test]
+    fn set_manifest_encryption_info() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+        let name = "test-package".to_string();
+        let encryption = EncryptionInfo {
+            scheme: "aes-256-gcm".to_string(),
+            key_ref: "kms://archetype/keys/1".to_string()
+        };
+
+        let mut contract = Contract::default();
+        contract.create_manifest(
+            name.clone(),
+            "0.0.1".to_string(),
+            "ipfs".to_string(),
+            "QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB".to_string(),
+            Vec::new(),
+            Some(encryption.clone()),
+            false,
+            None,
+            Vec::new()
+        ).unwrap();
+
+        let manifests = contract.get_versions_between(
+            context.signer_account_id.clone(),
+            name.clone(),
+            "0.0.1".to_string(),
+            "0.0.1".to_string()
+        ).unwrap();
+
+        assert_eq!(manifests[0].encryption, Some(encryption));
+    }
+
+    #[test]
+    fn reject_partial_encryption_info() {
+        let context = get_context(false);
+        testing_env!(context);
+
+        let mut contract = Contract::default();
+        let result = contract.create_manifest(
+            "test-package".to_string(),
+            "0.0.1".to_string(),
+            "ipfs".to_string(),
+            "QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB".to_string(),
+            Vec::new(),
+            Some(EncryptionInfo {
+                scheme: "aes-256-gcm".to_string(),
+                key_ref: "".to_string()
+            }),
+            false,
+            None,
+            Vec::new()
+        );
+        assert_eq!(result, Err(RegistryError::InvalidEncryption));
+    }
+
+    #[test]
+    fn packages_isolated_between_accounts() {
+        let name = "test-package".to_string();
+        let bob_cid = "QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB".to_string();
+        let alice_cid = "QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n".to_string();
+
+        let mut contract = Contract::default();
+        for (signer, cid) in [("bob_near", bob_cid.clone()), ("alice_near", alice_cid.clone())] {
+            testing_env!(VMContextBuilder::new().signer_account_id(signer.parse().unwrap()).attached_deposit(STORAGE_DEPOSIT).build());
+            contract.create_manifest(
+                name.clone(),
+                "0.0.1".to_string(),
+                "ipfs".to_string(),
+                cid,
+                Vec::new(),
+                None,
+                false,
+                None,
+                Vec::new()
+            ).unwrap();
+        }
+
+        assert_eq!(contract.get_latest_manifest("bob_near".parse().unwrap(), name.clone()).unwrap(), bob_cid);
+        assert_eq!(contract.get_latest_manifest("alice_near".parse().unwrap(), name.clone()).unwrap(), alice_cid);
+        assert_eq!(
+            contract.get_versions_between("bob_near".parse().unwrap(), name.clone(), "0.0.0".to_string(), "9.9.9".to_string()).unwrap().len(),
+            1
+        );
+    }
+
+    #[test]
+    fn attestations_isolated_between_attestors() {
+        let author: AccountId = "bob_near".parse().unwrap();
+        let name = "test-package".to_string();
+
+        let mut contract = self_attesting_contract();
+        testing_env!(get_context(false));
+        contract.create_manifest(
+            name.clone(),
+            "0.0.1".to_string(),
+            "ipfs".to_string(),
+            "QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB".to_string(),
+            Vec::new(),
+            None,
+            false,
+            None,
+            Vec::new()
+        ).unwrap();
+
+        for attestor in ["bob_near", "alice_near"] {
+            testing_env!(VMContextBuilder::new().signer_account_id(attestor.parse().unwrap()).attached_deposit(STORAGE_DEPOSIT).build());
+            contract.create_attestation(
+                name.clone(),
+                author.clone(),
+                "QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n".to_string(),
+                None,
+                None,
+                false,
+                None,
+                String::new(),
+                String::new(),
+                0
+            ).unwrap();
+        }
+
+        assert_eq!(contract.get_attestations(author.clone(), name.clone(), author.clone()).unwrap().len(), 1);
+        assert_eq!(contract.get_attestations("alice_near".parse().unwrap(), name.clone(), author.clone()).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn try_get_present_manifest() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+        let name = "test-package".to_string();
+
+        let mut contract = Contract::default();
+        contract.create_manifest(
+            name.clone(),
+            "0.0.1".to_string(),
+            "ipfs".to_string(),
+            "QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB".to_string(),
+            Vec::new(),
+            None,
+            false,
+            None,
+            Vec::new()
+        ).unwrap();
+
+        let manifest = contract.try_get_manifest(context.signer_account_id.clone(), name.clone(), "0.0.1".to_string()).unwrap();
+        assert_eq!(manifest.version, "0.0.1".to_string());
+        assert_eq!(manifest.cid, "QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB".to_string());
+        assert_eq!(manifest.content_type, "ipfs".to_string());
+    }
+
+    #[test]
+    fn try_get_absent_version() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+        let name = "test-package".to_string();
+
+        let mut contract = Contract::default();
+        contract.create_manifest(
+            name.clone(),
+            "0.0.1".to_string(),
+            "ipfs".to_string(),
+            "QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB".to_string(),
+            Vec::new(),
+            None,
+            false,
+            None,
+            Vec::new()
+        ).unwrap();
+
+        assert_eq!(contract.try_get_manifest(context.signer_account_id.clone(), name.clone(), "0.0.2".to_string()), None);
+        assert_eq!(contract.get_manifest(context.signer_account_id.clone(), name.clone(), "0.0.2".to_string()), None);
+    }
+
+    #[test]
+    fn try_get_absent_package() {
+        let context = get_context(true);
+        testing_env!(context.clone());
+        let contract = Contract::default();
+
+        assert_eq!(
+            contract.try_get_manifest(context.signer_account_id.clone(), "test-package".to_string(), "0.0.1".to_string()),
+            None
+        );
+    }
+
+    #[test]
+    fn list_versions_in_insertion_order() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+        let name = "test-package".to_string();
+
+        let mut contract = Contract::default();
+        for version in ["0.2.0", "0.1.0", "1.0.0"] {
+            contract.create_manifest(
+                name.clone(),
+                version.to_string(),
+                "ipfs".to_string(),
+                "QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB".to_string(),
+                Vec::new(),
+                None,
+                false,
+                None,
+                Vec::new()
+            ).unwrap();
+        }
+
+        assert_eq!(
+            contract.list_versions(context.signer_account_id.clone(), name.clone()).unwrap(),
+            vec!["0.2.0".to_string(), "0.1.0".to_string(), "1.0.0".to_string()]
+        );
+    }
+
+    #[test]
+    #[test]
+    fn reject_duplicate_version() {
+        let context = get_context(false);
+        testing_env!(context);
+        let name = "test-package".to_string();
+
+        let mut contract = Contract::default();
+        publish_versions(&mut contract, &name, &["0.0.1"]);
+
+        let result = try_publish(&mut contract, &name, "0.0.1");
+        assert_eq!(result, Err(RegistryError::VersionExists));
+    }
+
+    #[test]
+    fn get_packages_without_packages() {
+        let context = get_context(true);
+        testing_env!(context.clone());
+        let contract = Contract::default();
+
+        assert!(contract.get_packages(context.signer_account_id.clone()).is_empty());
+    }
+
+    #[test]
+    fn get_packages_with_one_package() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+
+        let mut contract = Contract::default();
+        for version in ["0.0.1", "0.0.2"] {
+            contract.create_manifest(
+                "test-package".to_string(),
+                version.to_string(),
+                "ipfs".to_string(),
+                "QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB".to_string(),
+                Vec::new(),
+                None,
+                false,
+                None,
+                Vec::new()
+            ).unwrap();
+        }
+
+        assert_eq!(contract.get_packages(context.signer_account_id.clone()), vec!["test-package".to_string()]);
+    }
+
+    #[test]
+    fn get_packages_with_multiple_packages() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+
+        let mut contract = Contract::default();
+        for name in ["first-package", "second-package", "third-package"] {
+            contract.create_manifest(
+                name.to_string(),
+                "0.0.1".to_string(),
+                "ipfs".to_string(),
+                "QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB".to_string(),
+                Vec::new(),
+                None,
+                false,
+                None,
+                Vec::new()
+            ).unwrap();
+        }
+
+        assert_eq!(
+            contract.get_packages(context.signer_account_id.clone()),
+            vec!["first-package".to_string(), "second-package".to_string(), "third-package".to_string()]
+        );
+    }
+
+    #[test]
+    fn verbose_logs_absent_at_events_level() {
+        let context = get_context(false);
+        testing_env!(context);
+
+        let mut contract = Contract::default();
+        assert_eq!(contract.get_config().log_level, LogLevel::Events);
+        try_publish(&mut contract, "test-package", "0.0.1").unwrap();
+
+        let logs = get_logs();
+        assert_eq!(logs.len(), 1);
+        assert!(logs[0].starts_with("EVENT_JSON:"));
+    }
+
+    #[test]
+    fn log_level_controls_logs() {
+        testing_env!(get_owner_context("bob_near"));
+        let mut contract = Contract::new("bob_near".parse().unwrap());
+        contract.set_log_level(LogLevel::Verbose);
+        try_publish(&mut contract, "test-package", "0.0.1").unwrap();
+        assert!(get_logs().iter().any(|log| log == "Writing manifest for test-package..."));
+        assert!(get_logs().last().unwrap().starts_with("EVENT_JSON:"));
+
+        testing_env!(get_owner_context("bob_near"));
+        contract.set_log_level(LogLevel::Off);
+        try_publish(&mut contract, "test-package", "0.0.2").unwrap();
+        assert!(get_logs().is_empty());
+    }
+
+    #[test]
+    fn emit_manifest_events() {
+        let context = get_context(false);
+        testing_env!(context);
+        let name = "test-package".to_string();
+
+        let mut contract = Contract::default();
+        contract.create_manifest(
+            name.clone(),
+            "0.0.1".to_string(),
+            "ipfs".to_string(),
+            "QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB".to_string(),
+            Vec::new(),
+            None,
+            false,
+            None,
+            Vec::new()
+        ).unwrap();
+
+        assert_eq!(
+            get_logs().last().unwrap(),
+            "EVENT_JSON:{\"standard\":\"attestation-registry\",\"version\":\"1.0.0\",\"event\":\"manifest_created\",\"data\":[{\"author\":\"bob_near\",\"package_name\":\"test-package\",\"version\":\"0.0.1\",\"cid\":\"QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB\"}]}"
+        );
+
+        contract.update_manifest(
+            name.clone(),
+            "0.0.1".to_string(),
+            "ipfs".to_string(),
+            "QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n".to_string()
+        ).unwrap();
+
+        assert_eq!(
+            get_logs().last().unwrap(),
+            "EVENT_JSON:{\"standard\":\"attestation-registry\",\"version\":\"1.0.0\",\"event\":\"manifest_updated\",\"data\":[{\"author\":\"bob_near\",\"package_name\":\"test-package\",\"version\":\"0.0.1\",\"cid\":\"QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n\"}]}"
+        );
+    }
+
+    #[test]
+    fn emit_attestation_event() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+        let name = "test-package".to_string();
+
+        let mut contract = self_attesting_contract();
+        contract.create_manifest(
+            name.clone(),
+            "0.0.1".to_string(),
+            "ipfs".to_string(),
+            "QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB".to_string(),
+            Vec::new(),
+            None,
+            false,
+            None,
+            Vec::new()
+        ).unwrap();
+
+        contract.create_attestation(
+            name.clone(),
+            context.signer_account_id.clone(),
+            "QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n".to_string(),
+            None,
+            None,
+            false,
+            None,
+            String::new(),
+            String::new(),
+            0
+        ).unwrap();
+
+        assert_eq!(
+            get_logs().last().unwrap(),
+            "EVENT_JSON:{\"standard\":\"attestation-registry\",\"version\":\"1.0.0\",\"event\":\"attestation_created\",\"data\":[{\"attestor\":\"bob_near\",\"author\":\"bob_near\",\"package_name\":\"test-package\",\"cid\":\"QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n\"}]}"
+        );
+    }
+
+    fn try_publish(contract: &mut Contract, name: &str, version: &str) -> Result<(), RegistryError> {
+        return contract.create_manifest(
+            name.to_string(),
+            version.to_string(),
+            "ipfs".to_string(),
+            "QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB".to_string(),
+            Vec::new(),
+            None,
+            false,
+            None,
+            Vec::new()
+        );
+    }
+
+    fn publish_versions(contract: &mut Contract, name: &str, versions: &[&str]) {
+        for version in versions {
+            try_publish(contract, name, version).unwrap();
+        }
+    }
+
+    #[test]
+    fn delete_middle_manifest() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+        let name = "test-package".to_string();
+
+        let mut contract = Contract::default();
+        publish_versions(&mut contract, &name, &["0.0.1", "0.0.2", "0.0.3"]);
+        contract.delete_manifest(name.clone(), "0.0.2".to_string()).unwrap();
+
+        assert_eq!(
+            contract.list_versions(context.signer_account_id.clone(), name.clone()).unwrap(),
+            vec!["0.0.1".to_string(), "0.0.3".to_string()]
+        );
+    }
+
+    #[test]
+    fn delete_last_manifest() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+        let name = "test-package".to_string();
+
+        let mut contract = Contract::default();
+        publish_versions(&mut contract, &name, &["0.0.1"]);
+        contract.delete_manifest(name.clone(), "0.0.1".to_string()).unwrap();
+
+        assert_eq!(contract.try_get_manifest(context.signer_account_id.clone(), name.clone(), "0.0.1".to_string()), None);
+        assert!(contract.get_packages(context.signer_account_id.clone()).is_empty());
+    }
+
+    #[test]
+    fn delete_last_manifest_clears_package_state() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+        let name = "test-package".to_string();
+        let namespace = Contract::generate_key(context.signer_account_id.clone(), name.clone());
+
+        let mut contract = Contract::default();
+        contract.set_versioning_scheme(name.clone(), VersioningScheme::Semver, false).unwrap();
+        publish_versions(&mut contract, &name, &["1.0.0"]);
+        contract.delete_manifest(name.clone(), "1.0.0".to_string()).unwrap();
+
+        assert!(!contract.compiled_types.contains_key(&namespace));
+        assert!(!contract.package_roots.contains_key(&namespace));
+        assert!(!contract.package_trees.contains_key(&namespace));
+        // The package starts over as freeform when the name is republished
+        publish_versions(&mut contract, &name, &["2024-spring-release"]);
+    }
+
+    #[test]
+    fn undo_last_publish_within_window() {
+        let mut context = get_context(false);
+        testing_env!(context.clone());
+        let name = "test-package".to_string();
+
+        let mut contract = Contract::default();
+        publish_versions(&mut contract, &name, &["0.0.1"]);
+        context.block_timestamp += 1;
+        testing_env!(context.clone());
+        publish_versions(&mut contract, &name, &["0.0.2"]);
+        context.block_timestamp += DEFAULT_UNDO_WINDOW_NS - 1;
+        testing_env!(context.clone());
+        contract.undo_last_publish(name.clone()).unwrap();
+
+        let author = context.signer_account_id.clone();
+        assert_eq!(contract.list_versions(author.clone(), name.clone()).unwrap(), vec!["0.0.1".to_string()]);
+        assert_eq!(contract.total_manifests(), 1);
+        assert_eq!(
+            contract.lookup_by_cid("QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB".to_string()),
+            Some((author.clone(), name.clone(), "0.0.1".to_string()))
+        );
+
+        // The remaining version was published a full window ago
+        let result = contract.undo_last_publish(name.clone());
+        assert_eq!(result, Err(RegistryError::UndoWindowElapsed));
+    }
+
+    #[test]
+    fn undo_only_version_removes_package() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+        let name = "test-package".to_string();
+
+        let mut contract = Contract::default();
+        publish_versions(&mut contract, &name, &["0.0.1"]);
+        contract.undo_last_publish(name.clone()).unwrap();
+
+        assert!(contract.get_packages(context.signer_account_id.clone()).is_empty());
+        assert_eq!(contract.total_packages(), 0);
+        assert_eq!(contract.total_manifests(), 0);
+    }
+
+    #[test]
+    fn reject_undo_after_window() {
+        let mut context = get_context(false);
+        testing_env!(context.clone());
+        let name = "test-package".to_string();
+
+        let mut contract = Contract::default();
+        publish_versions(&mut contract, &name, &["0.0.1"]);
+        context.block_timestamp += DEFAULT_UNDO_WINDOW_NS;
+        testing_env!(context.clone());
+
+        let result = contract.undo_last_publish(name.clone());
+        assert_eq!(result, Err(RegistryError::UndoWindowElapsed));
+        assert_eq!(
+            contract.list_versions(context.signer_account_id.clone(), name.clone()).unwrap(),
+            vec!["0.0.1".to_string()]
+        );
+    }
+
+    #[test]
+    fn delete_nonexistent_manifest() {
+        let context = get_context(false);
+        testing_env!(context);
+        let name = "test-package".to_string();
+
+        let mut contract = Contract::default();
+        publish_versions(&mut contract, &name, &["0.0.1"]);
+        let result = contract.delete_manifest(name.clone(), "0.0.2".to_string());
+        assert_eq!(result, Err(RegistryError::VersionNotFound));
+    }
+
+    #[test]
+    fn accept_valid_semver() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+        let name = "test-package".to_string();
+
+        let mut contract = Contract::default();
+        contract.set_versioning_scheme(name.clone(), VersioningScheme::Semver, false).unwrap();
+        publish_versions(&mut contract, &name, &["1.0.0", "1.1.0-beta.1+build.5"]);
+
+        assert_eq!(
+            contract.list_versions(context.signer_account_id.clone(), name.clone()).unwrap(),
+            vec!["1.0.0".to_string(), "1.1.0-beta.1+build.5".to_string()]
+        );
+    }
+
+    #[test]
+    fn reject_invalid_semver() {
+        let context = get_context(false);
+        testing_env!(context);
+        let name = "test-package".to_string();
+
+        let mut contract = Contract::default();
+        contract.set_versioning_scheme(name.clone(), VersioningScheme::Semver, false).unwrap();
+        let result = try_publish(&mut contract, &name, "1.0");
+        assert_eq!(result, Err(RegistryError::InvalidVersion));
+    }
+
+    #[test]
+    fn reject_versioning_scheme_for_invalid_name() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+
+        let mut contract = Contract::default();
+        let result = contract.set_versioning_scheme("Not A Package".to_string(), VersioningScheme::Semver, false);
+        assert_eq!(result, Err(RegistryError::InvalidPackageName));
+    }
+
+    #[test]
+    fn accept_freeform_version() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+        let name = "test-package".to_string();
+
+        let mut contract = Contract::default();
+        contract.set_versioning_scheme(name.clone(), VersioningScheme::Freeform, false).unwrap();
+        publish_versions(&mut contract, &name, &["2024-spring-release"]);
+
+        assert_eq!(
+            contract.list_versions(context.signer_account_id.clone(), name.clone()).unwrap(),
+            vec!["2024-spring-release".to_string()]
+        );
+    }
+
+    #[test]
+    fn validate_cid_formats() {
+        assert!(Contract::validate_cid("QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB"));
+        assert!(Contract::validate_cid("bafybeicn7i3soqdgr7dwnrwytgq4zxy7a5jpkizrvhm5mv6bgjd32wm3q4"));
+
+        assert!(!Contract::validate_cid(""));
+        assert!(!Contract::validate_cid("Qm"));
+        // Too short for a CIDv0
+        assert!(!Contract::validate_cid("QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4Ygpq"));
+        // 0, O, I and l are not part of the base58btc alphabet
+        assert!(!Contract::validate_cid("QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4Ygp0B"));
+        // Uppercase is not part of the lowercase base32 alphabet
+        assert!(!Contract::validate_cid("BAFYBEICN7I3SOQDGR7DWNRWYTGQ4ZXY7A5JPKIZRVHM5MV6BGJD32WM3Q4"));
+        assert!(!Contract::validate_cid("bafybeicn7i3soqdgr7dwnrwytgq4zxy7a5jpkizrvhm5mv6bgjd32wm3q1"));
+        assert!(!Contract::validate_cid("https://ipfs.io/ipfs/QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB"));
+        // Longer than any CIDv1 the registry accepts
+        assert!(!Contract::validate_cid(&format!("b{}", "a".repeat(MAX_CID_LENGTH))));
+    }
+
+    #[test]
+    fn reject_manifest_with_invalid_cid() {
+        let context = get_context(false);
+        testing_env!(context);
+
+        let mut contract = Contract::default();
+        let result = contract.create_manifest(
+            "test-package".to_string(),
+            "0.0.1".to_string(),
+            "ipfs".to_string(),
+            "not-a-cid".to_string(),
+            Vec::new(),
+            None,
+            false,
+            None,
+            Vec::new()
+        );
+        assert_eq!(result, Err(RegistryError::InvalidCid));
+    }
+
+    fn attest_times(contract: &mut Contract, author: AccountId, name: &str, count: usize) {
+        let cids = [
+            "QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB",
+            "QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n",
+            "bafybeicn7i3soqdgr7dwnrwytgq4zxy7a5jpkizrvhm5mv6bgjd32wm3q4",
+            "QmT78zSuBmuS4z925WZfrqQ1qHaJ56DQaTfyMUF7F8ff5o",
+            "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG"
+        ];
+
+        for i in 0..count {
+            contract.create_attestation(name.to_string(), author.clone(), cids[i % cids.len()].to_string(), None, None, false, None, String::new(), String::new(), 0).unwrap();
+        }
+    }
+
+    fn setup_paged_attestations() -> (Contract, AccountId, String) {
+        let context = get_context(false);
+        testing_env!(context.clone());
+        let author = context.signer_account_id.clone();
+        let name = "test-package".to_string();
+
+        let mut contract = self_attesting_contract();
+        publish_versions(&mut contract, &name, &["0.0.1"]);
+        attest_times(&mut contract, author.clone(), &name, 5);
+
+        return (contract, author, name);
+    }
+
+    #[test]
+    fn get_first_attestation_page() {
+        let (contract, author, name) = setup_paged_attestations();
+        let page = contract.get_attestations_paged(author.clone(), name.clone(), author.clone(), 0, 2).unwrap();
+
+        assert_eq!(page.len(), 2);
+        assert_eq!(page[0].cid, "QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB".to_string());
+        assert_eq!(page[1].cid, "QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n".to_string());
+    }
+
+    #[test]
+    fn get_middle_attestation_page() {
+        let (contract, author, name) = setup_paged_attestations();
+        let page = contract.get_attestations_paged(author.clone(), name.clone(), author.clone(), 2, 2).unwrap();
+
+        assert_eq!(page.len(), 2);
+        assert_eq!(page[0].cid, "bafybeicn7i3soqdgr7dwnrwytgq4zxy7a5jpkizrvhm5mv6bgjd32wm3q4".to_string());
+        assert_eq!(page[1].cid, "QmT78zSuBmuS4z925WZfrqQ1qHaJ56DQaTfyMUF7F8ff5o".to_string());
+    }
+
+    #[test]
+    fn get_attestation_page_past_end() {
+        let (contract, author, name) = setup_paged_attestations();
+
+        assert!(contract.get_attestations_paged(author.clone(), name.clone(), author.clone(), 10, 2).unwrap().is_empty());
+    }
+
+    #[test]
+    fn get_attestation_page_with_large_limit() {
+        let (contract, author, name) = setup_paged_attestations();
+        let page = contract.get_attestations_paged(author.clone(), name.clone(), author.clone(), 3, 100).unwrap();
+
+        assert_eq!(page.len(), 2);
+        assert_eq!(page[1].cid, "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG".to_string());
+    }
+
+    #[test]
+    fn revoke_only_attestation() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+        let author = context.signer_account_id.clone();
+        let name = "test-package".to_string();
+
+        let mut contract = self_attesting_contract();
+        publish_versions(&mut contract, &name, &["0.0.1"]);
+        attest_times(&mut contract, author.clone(), &name, 1);
+        contract.revoke_attestation(name.clone(), author.clone(), 0, false).unwrap();
+
+        assert!(contract.get_attestations(author.clone(), name.clone(), author.clone()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn revoke_one_of_several_attestations() {
+        let (mut contract, author, name) = setup_paged_attestations();
+        contract.revoke_attestation(name.clone(), author.clone(), 1, false).unwrap();
+
+        let cids = contract.get_attestations(author.clone(), name.clone(), author.clone()).unwrap()
+            .into_iter()
+            .map(|a| a.cid)
+            .collect::<Vec<String>>();
+
+        assert_eq!(cids, vec![
+            "QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB",
+            "bafybeicn7i3soqdgr7dwnrwytgq4zxy7a5jpkizrvhm5mv6bgjd32wm3q4",
+            "QmT78zSuBmuS4z925WZfrqQ1qHaJ56DQaTfyMUF7F8ff5o",
+            "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG"
+        ]);
+    }
+
+    #[test]
+    fn revoke_attestation_out_of_range() {
+        let (mut contract, author, name) = setup_paged_attestations();
+        let result = contract.revoke_attestation(name.clone(), author.clone(), 5, false);
+        assert_eq!(result, Err(RegistryError::AttestationIndexOutOfRange));
+    }
+
+    // Signature by the challenge key over the bytes of the QmPK1s... manifest cid
+    const MANIFEST_SIGNATURE: &str = "f36a3dc07b755643d792db7c87b3a285b0d5fd5ab456b4b0c202c59fb99c6bc960040fd250f6fb68eae9ba23f33852446475d8d099190b102c56d4de45c99a00";
+
+    #[test]
+    fn attest_with_valid_signature() {
+        let context = get_challenge_context();
+        testing_env!(context.clone());
+        let author = context.signer_account_id.clone();
+        let name = "test-package".to_string();
+
+        let mut contract = self_attesting_contract();
+        publish_versions(&mut contract, &name, &["0.0.1"]);
+        contract.create_attestation(
+            name.clone(),
+            author.clone(),
+            "QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n".to_string(),
+            None,
+            Some(from_hex(MANIFEST_SIGNATURE)),
+            false,
+            None,
+            String::new(),
+            String::new(),
+            0
+        ).unwrap();
+
+        assert_eq!(contract.get_attestations(author.clone(), name.clone(), author.clone()).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn reject_attestation_with_tampered_signature() {
+        let context = get_challenge_context();
+        testing_env!(context.clone());
+        let author = context.signer_account_id.clone();
+        let name = "test-package".to_string();
+
+        let mut contract = self_attesting_contract();
+        publish_versions(&mut contract, &name, &["0.0.1"]);
+
+        let mut signature = from_hex(MANIFEST_SIGNATURE);
+        signature[10] ^= 0xff;
+        let result = contract.create_attestation(
+            name.clone(),
+            author.clone(),
+            "QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n".to_string(),
+            None,
+            Some(signature),
+            false,
+            None,
+            String::new(),
+            String::new(),
+            0
+        );
+        assert_eq!(result, Err(RegistryError::InvalidAttestationSignature));
+    }
+
+    #[test]
+    fn count_several_attestations() {
+        let (contract, author, name) = setup_paged_attestations();
+
+        assert_eq!(contract.count_attestations(author.clone(), name.clone(), author.clone()).unwrap(), 5);
+    }
+
+    #[test]
+    fn purge_author_data_in_steps() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+        let author = context.signer_account_id.clone();
+
+        let mut contract = self_attesting_contract();
+        publish_versions(&mut contract, "first-package", &["0.0.1", "0.0.2"]);
+        publish_versions(&mut contract, "second-package", &["0.0.1"]);
+        publish_versions(&mut contract, "third-package", &["0.0.1"]);
+        attest_times(&mut contract, author.clone(), "first-package", 2);
+        attest_times(&mut contract, author.clone(), "third-package", 1);
+        contract.set_tag("third-package".to_string(), "latest".to_string(), "0.0.1".to_string()).unwrap();
+
+        assert_eq!(contract.purge_author_data(author.clone(), 0, 2), Ok(1));
+        assert_eq!(contract.get_packages(author.clone()), vec!["third-package".to_string()]);
+        assert_eq!(contract.total_attestations_for_author(author.clone()), 1);
+        assert_eq!(contract.purge_author_data(author.clone(), 0, 2), Ok(0));
+        // Purging an author with nothing left is a no-op
+        assert_eq!(contract.purge_author_data(author.clone(), 0, 2), Ok(0));
+
+        assert!(contract.get_packages(author.clone()).is_empty());
+        assert_eq!(contract.total_packages(), 0);
+        assert_eq!(contract.total_manifests(), 0);
+        assert_eq!(contract.total_attestations_for_author(author.clone()), 0);
+        assert_eq!(contract.lookup_by_cid("QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB".to_string()), None);
+        assert!(contract.find_authors("first-package".to_string()).is_empty());
+        assert!(contract.get_attestations(author.clone(), "first-package".to_string(), author.clone()).is_none());
+
+        // Republishing a purged name starts without its old attestations or tags
+        publish_versions(&mut contract, "third-package", &["0.0.1"]);
+        assert!(contract.get_attestors(author.clone(), "third-package".to_string()).is_empty());
+        assert!(contract.list_dist_tags(author.clone(), "third-package".to_string()).is_empty());
+        assert_eq!(contract.total_attestations_for_author(author), 0);
+    }
+
+    #[test]
+    fn purge_author_data_by_owner_only() {
+        testing_env!(get_owner_context("bob_near"));
+        let mut contract = Contract::new("owner_near".parse().unwrap());
+        publish_versions(&mut contract, "test-package", &["0.0.1"]);
+
+        testing_env!(get_owner_context("alice_near"));
+        let author: AccountId = "bob_near".parse().unwrap();
+        assert_eq!(contract.purge_author_data(author.clone(), 0, 10), Err(RegistryError::NotAuthorOrOwner));
+
+        testing_env!(get_owner_context("owner_near"));
+        assert_eq!(contract.purge_author_data(author.clone(), 0, 10), Ok(0));
+        assert!(contract.get_packages(author).is_empty());
+    }
+
+    #[test]
+    fn list_attestations_across_author_packages() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+        let author = context.signer_account_id.clone();
+
+        let mut contract = self_attesting_contract();
+        publish_versions(&mut contract, "first-package", &["0.0.1"]);
+        publish_versions(&mut contract, "second-package", &["0.0.1"]);
+        publish_versions(&mut contract, "third-package", &["0.0.1"]);
+        attest_times(&mut contract, author.clone(), "first-package", 2);
+        attest_times(&mut contract, author.clone(), "third-package", 1);
+
+        let all = contract.get_author_attestations(author.clone(), 0, 10);
+        let packages = all.iter().map(|(name, _)| name.as_str()).collect::<Vec<&str>>();
+        assert_eq!(packages, vec!["first-package", "first-package", "third-package"]);
+        assert_eq!(all[1].1.cid, "QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n".to_string());
+
+        // The second page starts at second-package, which has no attestations
+        let page = contract.get_author_attestations(author.clone(), 1, 2);
+        assert_eq!(page.len(), 1);
+        assert_eq!(page[0].0, "third-package".to_string());
+        assert!(contract.get_author_attestations(author, 3, 10).is_empty());
+    }
+
+    #[test]
+    fn views_return_none_for_missing_entries() {
+        let (contract, author, name) = setup_paged_attestations();
+        let stranger: AccountId = "alice_near".parse().unwrap();
+        let missing = "missing-package".to_string();
+
+        assert_eq!(contract.get_latest_manifest(stranger.clone(), name.clone()), None);
+        assert_eq!(contract.get_latest_manifest(author.clone(), missing.clone()), None);
+        assert_eq!(contract.list_versions(stranger.clone(), name.clone()), None);
+        assert_eq!(contract.list_versions(author.clone(), missing.clone()), None);
+        assert_eq!(contract.get_manifest_history(author.clone(), missing.clone()), None);
+        assert_eq!(contract.get_package_root(author.clone(), missing.clone()), None);
+        assert_eq!(contract.get_versions_between(author.clone(), name.clone(), "x".to_string(), "1.0.0".to_string()), None);
+
+        assert!(contract.get_attestations(stranger.clone(), name.clone(), author.clone()).is_none());
+        assert!(contract.get_attestations(author.clone(), missing.clone(), author.clone()).is_none());
+        assert_eq!(contract.count_attestations(author.clone(), missing, author.clone()), None);
+
+        // setup_paged_attestations records five attestations
+        assert!(contract.get_attestation(author.clone(), name.clone(), author.clone(), 4).is_some());
+        assert!(contract.get_attestation(author.clone(), name.clone(), author.clone(), 5).is_none());
+        assert!(contract.get_attestation(stranger, name, author, 0).is_none());
+    }
+
+    #[test]
+    fn count_attestations_for_unknown_attestor() {
+        let (contract, author, name) = setup_paged_attestations();
+        assert_eq!(contract.count_attestations("alice_near".parse().unwrap(), name.clone(), author.clone()), None);
+    }
+
+    // bob_near attests to first-package only, second-package exists but has no attestations from him
+    fn setup_attestor_of_other_package() -> (Contract, AccountId) {
+        let context = get_context(false);
+        testing_env!(context.clone());
+        let author = context.signer_account_id.clone();
+
+        let mut contract = self_attesting_contract();
+        publish_versions(&mut contract, "first-package", &["0.0.1"]);
+        publish_versions(&mut contract, "second-package", &["0.0.1"]);
+        attest_times(&mut contract, author.clone(), "first-package", 1);
+        return (contract, author);
+    }
+
+    #[test]
+    fn revoke_on_package_never_attested() {
+        let (mut contract, author) = setup_attestor_of_other_package();
+
+        let result = contract.revoke_attestation("second-package".to_string(), author, 0, false);
+        assert_eq!(result, Err(RegistryError::AttestorNotFound));
+    }
+
+    #[test]
+    fn count_on_package_never_attested() {
+        let (contract, author) = setup_attestor_of_other_package();
+        assert_eq!(contract.count_attestations(author.clone(), "second-package".to_string(), author), None);
+    }
+
+    #[test]
+    fn get_attestations_as_view() {
+        let (_, author, name) = setup_paged_attestations();
+        testing_env!(get_context(true));
+
+        // The contract state written above is read back without a mutable borrow
+        let contract = self_attesting_contract();
+        assert_eq!(contract.get_attestations(author.clone(), name.clone(), author.clone()).unwrap().len(), 5);
+        assert_eq!(
+            contract.get_attestation(author.clone(), name.clone(), author.clone(), 0).unwrap().cid,
+            "QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB".to_string()
+        );
+    }
+
+    #[test]
+    fn set_manifest_timestamps() {
+        let context = VMContextBuilder::new()
+            .signer_account_id("bob_near".parse().unwrap())
+            .block_timestamp(1_700_000_000_000_000_000)
+            .attached_deposit(STORAGE_DEPOSIT)
+            .build();
+        testing_env!(context.clone());
+        let name = "test-package".to_string();
+
+        let mut contract = Contract::default();
+        publish_versions(&mut contract, &name, &["0.0.1"]);
+
+        let manifest = contract.try_get_manifest(context.signer_account_id.clone(), name.clone(), "0.0.1".to_string()).unwrap();
+        assert_ne!(manifest.created_at, 0);
+        assert_eq!(manifest.created_at, context.block_timestamp);
+        assert_eq!(manifest.updated_at, 0);
+
+        testing_env!(VMContextBuilder::new()
+            .signer_account_id("bob_near".parse().unwrap())
+            .block_timestamp(1_800_000_000_000_000_000)
+            .attached_deposit(STORAGE_DEPOSIT)
+            .build());
+
+        contract.update_manifest(
+            name.clone(),
+            "0.0.1".to_string(),
+            "ipfs".to_string(),
+            "QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n".to_string()
+        ).unwrap();
+
+        let updated = contract.try_get_manifest(context.signer_account_id.clone(), name.clone(), "0.0.1".to_string()).unwrap();
+        assert_eq!(updated.created_at, context.block_timestamp);
+        assert_eq!(updated.updated_at, 1_800_000_000_000_000_000);
+    }
+
+    // Writes contract state in the layout deployed before per-account prefixes, with one package and
+    // one self-attestation by the signer of the context
+    fn write_old_layout(context: &VMContext, name: &str) {
+        use crate::migrate::{OldAttestation, OldContract, OldManifest};
+
+        let author = context.signer_account_id.clone();
+        let mut old = OldContract {
+            packages: LookupMap::new(PrefixKeys::Package),
+            attestations: LookupMap::new(PrefixKeys::Attestation),
+            compiled_types: LookupMap::new(PrefixKeys::Types),
+            type_list: UnorderedMap::new(PrefixKeys::TypeList)
+        };
+
+        let mut releases = LookupMap::new(PrefixKeys::Manifest);
+        releases.insert(&name.to_string(), &vec![OldManifest {
+            version: "0.0.1".to_string(),
+            cid: "QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB".to_string(),
+            content_type: "ipfs".to_string(),
+            types: Vec::new()
+        }]);
+        old.packages.insert(&author, &releases);
+
+        let mut buckets = LookupMap::new(PrefixKeys::Attestation);
+        buckets.insert(&Contract::generate_key(author.clone(), name.to_string()), &vec![OldAttestation {
+            pubkey: context.signer_account_pk.clone(),
+            cid: "QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n".to_string()
+        }]);
+        old.attestations.insert(&author, &buckets);
+        old.type_list.insert(&"Widget".to_string(), &1);
+        near_sdk::env::state_write(&old);
+    }
+
+    #[test]
+    fn migrate_old_layout() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+        let author = context.signer_account_id.clone();
+        let name = "test-package".to_string();
+        write_old_layout(&context, &name);
+
+        let mut contract = Contract::migrate("owner_near".parse().unwrap());
+        assert_eq!(contract.owner_id, "owner_near".parse::<AccountId>().unwrap());
+        contract.migrate_packages(author.clone(), vec![name.clone()]);
+        contract.migrate_attestations(author.clone(), vec![(author.clone(), name.clone())]);
+
+        let manifest = contract.try_get_manifest(author.clone(), name.clone(), "0.0.1".to_string()).unwrap();
+        assert_eq!(manifest.cid, "QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB".to_string());
+        assert_eq!(manifest.created_at, 0);
+        assert_eq!(manifest.updated_at, 0);
+        assert_eq!(manifest.encryption, None);
+        assert_eq!(contract.get_packages(author.clone()), vec![name.clone()]);
+        assert_eq!(contract.type_list.get(&"Widget".to_string()), Some(1));
+
+        let attestation = contract.get_attestation(author.clone(), name.clone(), author.clone(), 0).unwrap();
+        assert_eq!(attestation.cid, "QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n".to_string());
+        assert_eq!(attestation.note, None);
+        assert_eq!(attestation.reconfirmed_at_ns, 0);
+        assert_eq!(attestation.claim_type, "");
+        assert_eq!(attestation.schema_version, "");
+    }
+
+    #[test]
+    fn unmigrated_author_reads_empty_and_can_publish() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+        let author = context.signer_account_id.clone();
+        let name = "test-package".to_string();
+        write_old_layout(&context, &name);
+
+        let mut contract = Contract::migrate("owner_near".parse().unwrap());
+
+        // Legacy entries live under the old prefixes and are invisible until migrated
+        assert!(contract.packages.get(&author).is_none());
+        assert!(contract.attestations.get(&author).is_none());
+        assert_eq!(contract.get_latest_manifest_opt(author.clone(), name.clone()), None);
+
+        publish_versions(&mut contract, "other-package", &["1.0.0"]);
+        assert_eq!(contract.list_versions(author.clone(), "other-package".to_string()).unwrap(), vec!["1.0.0"]);
+        assert_eq!(contract.get_latest_manifest_opt(author.clone(), name.clone()), None);
+
+        contract.migrate_packages(author.clone(), vec![name.clone()]);
+        assert_eq!(contract.list_versions(author.clone(), name.clone()).unwrap(), vec!["0.0.1"]);
+        assert_eq!(contract.list_versions(author, "other-package".to_string()).unwrap(), vec!["1.0.0"]);
+    }
+
+    fn get_owner_context(predecessor: &str) -> VMContext {
+        VMContextBuilder::new()
+            .signer_account_id(predecessor.parse().unwrap())
+            .predecessor_account_id(predecessor.parse().unwrap())
+            .attached_deposit(STORAGE_DEPOSIT)
+            .build()
+    }
+
+    #[test]
+    fn owner_can_transfer_ownership() {
+        testing_env!(get_owner_context("owner_near"));
+        let mut contract = Contract::new("owner_near".parse().unwrap());
+        contract.set_owner("bob_near".parse().unwrap());
+
+        assert_eq!(contract.get_owner(), "bob_near".parse::<AccountId>().unwrap());
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the owner can call this method")]
+    fn non_owner_cannot_transfer_ownership() {
+        testing_env!(get_owner_context("bob_near"));
+        let mut contract = Contract::new("owner_near".parse().unwrap());
+        contract.set_owner("bob_near".parse().unwrap());
+    }
+
+    #[test]
+    fn reject_writes_while_paused() {
+        testing_env!(get_owner_context("owner_near"));
+        let mut contract = Contract::new("owner_near".parse().unwrap());
+        contract.pause();
+
+        let result = try_publish(&mut contract, "test-package", "0.0.1");
+        assert_eq!(result, Err(RegistryError::ContractPaused));
+    }
+
+    #[test]
+    fn allow_writes_after_unpause() {
+        testing_env!(get_owner_context("owner_near"));
+        let author: AccountId = "owner_near".parse().unwrap();
+        let mut contract = Contract::new(author.clone());
+        contract.pause();
+        assert!(contract.is_paused());
+
+        contract.unpause();
+        publish_versions(&mut contract, "test-package", &["0.0.1"]);
+
+        assert_eq!(contract.list_versions(author, "test-package".to_string()).unwrap(), vec!["0.0.1".to_string()]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the owner can call this method")]
+    fn non_owner_cannot_pause() {
+        testing_env!(get_owner_context("bob_near"));
+        let mut contract = Contract::new("owner_near".parse().unwrap());
+        contract.pause();
+    }
+
+    #[test]
+    fn refund_excess_storage_deposit() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+
+        let mut contract = Contract::default();
+        publish_versions(&mut contract, "test-package", &["0.0.1"]);
+
+        let receipts = get_created_receipts();
+        assert_eq!(receipts.len(), 1);
+        assert_eq!(receipts[0].receiver_id, context.predecessor_account_id);
+    }
+
+    #[test]
+    fn reject_insufficient_storage_deposit() {
+        testing_env!(VMContextBuilder::new()
+            .signer_account_id("bob_near".parse().unwrap())
+            .attached_deposit(NearToken::from_yoctonear(1))
+            .build());
+
+        let mut contract = Contract::default();
+        let result = try_publish(&mut contract, "test-package", "0.0.1");
+        assert!(matches!(result, Err(RegistryError::InsufficientDeposit(_))));
+    }
+
+    #[test]
+    fn reject_settings_without_storage_deposit() {
+        testing_env!(VMContextBuilder::new()
+            .signer_account_id("bob_near".parse().unwrap())
+            .predecessor_account_id("bob_near".parse().unwrap())
+            .build());
+
+        let mut contract = Contract::default();
+        let result = contract.set_publish_allowlist(vec!["alice_near".parse().unwrap()]);
+        assert!(matches!(result, Err(RegistryError::InsufficientDeposit(_))));
+        let result = contract.set_versioning_scheme("test-package".to_string(), VersioningScheme::Semver, false);
+        assert!(matches!(result, Err(RegistryError::InsufficientDeposit(_))));
+        let result = contract.set_default_content_type("json".to_string());
+        assert!(matches!(result, Err(RegistryError::InsufficientDeposit(_))));
+    }
+
+    #[test]
+    fn reject_package_changes_without_storage_deposit() {
+        testing_env!(get_context(false));
+        let name = "test-package".to_string();
+
+        let mut contract = Contract::default();
+        publish_versions(&mut contract, &name, &["0.0.1"]);
+
+        testing_env!(VMContextBuilder::new()
+            .signer_account_id("bob_near".parse().unwrap())
+            .predecessor_account_id("bob_near".parse().unwrap())
+            .build());
+
+        // A CIDv1 is longer than the CIDv0 it replaces so the update grows storage
+        let result = contract.update_manifest(
+            name.clone(),
+            "0.0.1".to_string(),
+            "ipfs".to_string(),
+            "bafybeicn7i3soqdgr7dwnrwytgq4zxy7a5jpkizrvhm5mv6bgjd32wm3q4".to_string()
+        );
+        assert!(matches!(result, Err(RegistryError::InsufficientDeposit(_))));
+        let result = contract.set_allow_self_attestation(name.clone(), false);
+        assert!(matches!(result, Err(RegistryError::InsufficientDeposit(_))));
+        let result = contract.register_author_key(CHALLENGE_PK.parse().unwrap());
+        assert!(matches!(result, Err(RegistryError::InsufficientDeposit(_))));
+        let result = contract.offer_package_transfer(name, "alice_near".parse().unwrap());
+        assert!(matches!(result, Err(RegistryError::InsufficientDeposit(_))));
+    }
+
+    #[test]
+    fn get_full_latest_manifest() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+        let name = "test-package".to_string();
+
+        let mut contract = Contract::default();
+        publish_versions(&mut contract, &name, &["0.0.1"]);
+        contract.create_manifest(
+            name.clone(),
+            "0.0.2".to_string(),
+            "json".to_string(),
+            "QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n".to_string(),
+            Vec::new(),
+            None,
+            false,
+            None,
+            Vec::new()
+        ).unwrap();
+
+        let second = contract.try_get_manifest(context.signer_account_id.clone(), name.clone(), "0.0.2".to_string()).unwrap();
+        let latest = contract.get_latest_manifest_full(context.signer_account_id.clone(), name.clone()).unwrap();
+        assert_eq!(latest, second);
+        assert_eq!(latest.content_type, "json".to_string());
+        assert_eq!(contract.get_latest_manifest(context.signer_account_id.clone(), name).unwrap(), latest.cid);
+    }
+
+    #[test]
+    fn update_missing_version() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+        let name = "test-package".to_string();
+
+        let mut contract = Contract::default();
+        publish_versions(&mut contract, &name, &["0.0.1"]);
+        let result = contract.update_manifest(
+            name,
+            "0.0.2".to_string(),
+            "ipfs".to_string(),
+            "QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n".to_string()
+        );
+        assert_eq!(result, Err(RegistryError::VersionNotFound));
+    }
+
+    #[test]
+    fn update_other_authors_package() {
+        let mut contract = Contract::default();
+        testing_env!(get_owner_context("alice_near"));
+        publish_versions(&mut contract, "alice-package", &["0.0.1"]);
+
+        testing_env!(get_context(false));
+        publish_versions(&mut contract, "bob-package", &["0.0.1"]);
+        let result = contract.update_manifest(
+            "alice-package".to_string(),
+            "0.0.1".to_string(),
+            "ipfs".to_string(),
+            "QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n".to_string()
+        );
+        assert_eq!(result, Err(RegistryError::PackageNotFound));
+    }
+
+    #[test]
+    fn list_versions_filtered_by_type() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+        let name = "test-package".to_string();
+
+        let mut contract = Contract::default();
+        for (version, content_type) in [("0.0.1", "ipfs"), ("0.0.2", "hyperfiles"), ("0.0.3", "ipfs")] {
+            contract.create_manifest(
+                name.clone(),
+                version.to_string(),
+                content_type.to_string(),
+                "QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB".to_string(),
+                Vec::new(),
+                None,
+                false,
+                None,
+                Vec::new()
+            ).unwrap();
+        }
+
+        let author = context.signer_account_id.clone();
+        let versions = contract.list_versions_by_type(author.clone(), name.clone(), "ipfs".to_string()).unwrap()
+            .into_iter()
+            .map(|m| m.version)
+            .collect::<Vec<String>>();
+
+        assert_eq!(versions, vec!["0.0.1", "0.0.3"]);
+        assert!(contract.list_versions_by_type(author, name, "json".to_string()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn list_author_manifests_by_content_type() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+        let author = context.signer_account_id.clone();
+
+        let mut contract = Contract::default();
+        contract.batch_create_manifests(
+            "first-package".to_string(),
+            vec![
+                ("0.0.1".to_string(), "ipfs".to_string(), "QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB".to_string()),
+                ("0.0.2".to_string(), "json".to_string(), "QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n".to_string())
+            ],
+            false
+        ).unwrap();
+        contract.batch_create_manifests(
+            "second-package".to_string(),
+            vec![
+                ("1.0.0".to_string(), "json".to_string(), "bafybeicn7i3soqdgr7dwnrwytgq4zxy7a5jpkizrvhm5mv6bgjd32wm3q4".to_string()),
+                ("1.0.1".to_string(), "ipfs".to_string(), "QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB".to_string())
+            ],
+            false
+        ).unwrap();
+
+        let found = contract.author_manifests_by_content_type(author.clone(), "JSON".to_string(), 0, 10)
+            .into_iter()
+            .map(|(name, m)| (name, m.version))
+            .collect::<Vec<(String, String)>>();
+        assert_eq!(found, vec![
+            ("first-package".to_string(), "0.0.2".to_string()),
+            ("second-package".to_string(), "1.0.0".to_string())
+        ]);
+
+        let page = contract.author_manifests_by_content_type(author.clone(), "ipfs".to_string(), 1, 1);
+        assert_eq!(page.len(), 1);
+        assert_eq!((page[0].0.as_str(), page[0].1.version.as_str()), ("second-package", "1.0.1"));
+        assert!(contract.author_manifests_by_content_type(author, "hyperfiles".to_string(), 0, 10).is_empty());
+    }
+
+    #[test]
+    fn batch_publish_manifests() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+        let name = "test-package".to_string();
+
+        let mut contract = Contract::default();
+        publish_versions(&mut contract, &name, &["0.0.1"]);
+        contract.batch_create_manifests(
+            name.clone(),
+            vec![
+                ("0.0.2".to_string(), "ipfs".to_string(), "QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n".to_string()),
+                ("0.0.3".to_string(), "json".to_string(), "bafybeicn7i3soqdgr7dwnrwytgq4zxy7a5jpkizrvhm5mv6bgjd32wm3q4".to_string())
+            ],
+            false
+        ).unwrap();
+
+        let author = context.signer_account_id.clone();
+        assert_eq!(contract.list_versions(author.clone(), name.clone()).unwrap(), vec!["0.0.1", "0.0.2", "0.0.3"]);
+        assert_eq!(contract.get_latest_manifest_full(author, name).unwrap().content_type, "json".to_string());
+    }
+
+    #[test]
+    fn batch_with_duplicate_rolls_back() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+        let name = "test-package".to_string();
+
+        let mut contract = Contract::default();
+        publish_versions(&mut contract, &name, &["0.0.1"]);
+
+        let result = contract.batch_create_manifests(
+            name.clone(),
+            vec![
+                ("0.0.2".to_string(), "ipfs".to_string(), "QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n".to_string()),
+                ("0.0.2".to_string(), "ipfs".to_string(), "QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB".to_string())
+            ],
+            false
+        );
+
+        assert_eq!(result, Err(RegistryError::VersionExists));
+        assert_eq!(contract.list_versions(context.signer_account_id.clone(), name).unwrap(), vec!["0.0.1"]);
+    }
+
+    fn batch_entries(count: usize) -> Vec<(String, String, String)> {
+        return (0..count)
+            .map(|i| (format!("1.0.{i}"), "ipfs".to_string(), "QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB".to_string()))
+            .collect();
+    }
+
+    #[test]
+    fn reject_batch_over_max_size() {
+        testing_env!(get_owner_context("owner_near"));
+        let mut contract = Contract::new("owner_near".parse().unwrap());
+        contract.set_max_batch_size(3);
+
+        testing_env!(get_context(false));
+        let result = contract.batch_create_manifests("test-package".to_string(), batch_entries(4), false);
+        assert_eq!(result, Err(RegistryError::BatchTooLarge(3)));
+        assert!(contract.get_packages("bob_near".parse().unwrap()).is_empty());
+    }
+
+    #[test]
+    fn accept_batch_within_max_size() {
+        testing_env!(get_owner_context("owner_near"));
+        let mut contract = Contract::new("owner_near".parse().unwrap());
+        contract.set_max_batch_size(3);
+
+        testing_env!(get_context(false));
+        contract.batch_create_manifests("test-package".to_string(), batch_entries(3), false).unwrap();
+        assert_eq!(
+            contract.list_versions("bob_near".parse().unwrap(), "test-package".to_string()).unwrap(),
+            vec!["1.0.0", "1.0.1", "1.0.2"]
+        );
+    }
+
+    #[test]
+    fn default_batch_size_cap() {
+        testing_env!(get_context(false));
+        let mut contract = Contract::default();
+
+        let entries = batch_entries(DEFAULT_MAX_BATCH_SIZE as usize + 1);
+        let result = contract.batch_create_manifests("test-package".to_string(), entries, false);
+        assert_eq!(result, Err(RegistryError::BatchTooLarge(DEFAULT_MAX_BATCH_SIZE)));
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the owner can call this method")]
+    fn non_owner_cannot_set_max_batch_size() {
+        testing_env!(get_owner_context("owner_near"));
+        let mut contract = Contract::new("owner_near".parse().unwrap());
+
+        testing_env!(get_owner_context("bob_near"));
+        contract.set_max_batch_size(1);
+    }
+
+    #[test]
+    fn get_config_reflects_owner_settings() {
+        testing_env!(get_owner_context("owner_near"));
+        let mut contract = Contract::new("owner_near".parse().unwrap());
+        assert_eq!(contract.get_config(), Config {
+            max_batch_size: DEFAULT_MAX_BATCH_SIZE,
+            paused: false,
+            max_versions_per_package: 0,
+            self_attestation_allowed: false,
+            blocked_cids: 0,
+            undo_window_ns: DEFAULT_UNDO_WINDOW_NS,
+            log_level: LogLevel::Events
+        });
+
+        contract.set_max_batch_size(10);
+        contract.set_max_versions_per_package(5);
+        contract.set_self_attestation_allowed(true);
+        contract.set_undo_window(60);
+        contract.set_log_level(LogLevel::Verbose);
+        contract.block_cid("QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB".to_string(), "hosts malware".to_string());
+        contract.block_cid("QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n".to_string(), "hosts malware".to_string());
+        // Changing the reason of an already blocked cid does not count it twice
+        contract.block_cid("QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n".to_string(), "phishing".to_string());
+        contract.unblock_cid("QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB".to_string());
+        contract.pause();
+
+        assert_eq!(contract.get_config(), Config {
+            max_batch_size: 10,
+            paused: true,
+            max_versions_per_package: 5,
+            self_attestation_allowed: true,
+            blocked_cids: 1,
+            undo_window_ns: 60,
+            log_level: LogLevel::Verbose
+        });
+    }
+
+    #[test]
+    fn list_distinct_attestors() {
+        let author: AccountId = "bob_near".parse().unwrap();
+        let name = "test-package".to_string();
+
+        let mut contract = self_attesting_contract();
+        testing_env!(get_context(false));
+        publish_versions(&mut contract, &name, &["0.0.1"]);
+
+        for (attestor, cid) in [
+            ("alice_near", "QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB"),
+            ("carol_near", "QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB"),
+            ("alice_near", "QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n"),
+            ("carol_near", "QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n")
+        ] {
+            testing_env!(get_owner_context(attestor));
+            contract.create_attestation(name.clone(), author.clone(), cid.to_string(), None, None, false, None, String::new(), String::new(), 0).unwrap();
+        }
+
+        let attestors = contract.get_attestors(author, name);
+        assert_eq!(attestors.len(), 2);
+        assert_eq!(attestors, vec!["alice_near".parse::<AccountId>().unwrap(), "carol_near".parse().unwrap()]);
+    }
+
+    #[test]
+    fn resolve_version_to_cid_and_content_type() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+        let author = context.signer_account_id.clone();
+        let name = "test-package".to_string();
+
+        let mut contract = Contract::default();
+        publish_versions(&mut contract, &name, &["0.0.1"]);
+
+        assert_eq!(
+            contract.resolve(author.clone(), name.clone(), "0.0.1".to_string()),
+            Some(("QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB".to_string(), "ipfs".to_string()))
+        );
+        assert_eq!(contract.resolve(author, name, "0.0.2".to_string()), None);
+    }
+
+    #[test]
+    fn accept_valid_package_name() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+
+        let mut contract = Contract::default();
+        publish_versions(&mut contract, "my_package.core-2", &["0.0.1"]);
+
+        assert_eq!(contract.get_packages(context.signer_account_id.clone()), vec!["my_package.core-2".to_string()]);
+    }
+
+    #[test]
+    fn reject_empty_package_name() {
+        testing_env!(get_context(false));
+        let mut contract = Contract::default();
+        let result = try_publish(&mut contract, "", "0.0.1");
+        assert_eq!(result, Err(RegistryError::InvalidPackageName));
+    }
+
+    #[test]
+    fn reject_uppercase_package_name() {
+        testing_env!(get_context(false));
+        let mut contract = Contract::default();
+        let result = try_publish(&mut contract, "Test-Package", "0.0.1");
+        assert_eq!(result, Err(RegistryError::InvalidPackageName));
+    }
+
+    #[test]
+    fn reject_overlong_package_name() {
+        testing_env!(get_context(false));
+        let mut contract = Contract::default();
+        let result = try_publish(&mut contract, &"a".repeat(MAX_PACKAGE_NAME_LENGTH + 1), "0.0.1");
+        assert_eq!(result, Err(RegistryError::InvalidPackageName));
+    }
+
+    #[test]
+    fn get_latest_manifest_by_time() {
+        let name = "test-package".to_string();
+        let mut contract = Contract::default();
+
+        for (version, timestamp) in [("0.0.2", 1_800_000_000_000_000_000), ("0.0.1", 1_700_000_000_000_000_000)] {
+            testing_env!(VMContextBuilder::new()
+                .signer_account_id("bob_near".parse().unwrap())
+                .block_timestamp(timestamp)
+                .attached_deposit(STORAGE_DEPOSIT)
+                .build());
+            publish_versions(&mut contract, &name, &[version]);
+        }
+
+        let author: AccountId = "bob_near".parse().unwrap();
+        assert_eq!(contract.get_latest_by_time(author.clone(), name.clone()).unwrap().version, "0.0.2".to_string());
+        assert_eq!(contract.get_latest_manifest_full(author, name).unwrap().version, "0.0.1".to_string());
+    }
+
+    #[test]
+    fn remove_existing_package() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+        let author = context.signer_account_id.clone();
+        let name = "test-package".to_string();
+
+        let mut contract = Contract::default();
+        publish_versions(&mut contract, &name, &["0.0.1", "0.0.2"]);
+        contract.remove_package(name.clone()).unwrap();
+
+        assert_eq!(contract.get_latest_manifest_opt(author.clone(), name.clone()), None);
+        assert_eq!(contract.try_get_manifest(author.clone(), name, "0.0.1".to_string()), None);
+        assert!(contract.get_packages(author).is_empty());
+    }
+
+    #[test]
+    fn remove_missing_package() {
+        testing_env!(get_context(false));
+
+        let mut contract = Contract::default();
+        publish_versions(&mut contract, "test-package", &["0.0.1"]);
+        let result = contract.remove_package("other-package".to_string());
+        assert_eq!(result, Err(RegistryError::PackageNotFound));
+    }
+
+    #[test]
+    fn report_error_variants() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+        let author = context.signer_account_id.clone();
+        let name = "test-package".to_string();
+
+        let mut contract = Contract::default();
+        assert_eq!(contract.remove_package(name.clone()), Err(RegistryError::NoPackagesFound));
+        assert_eq!(contract.batch_create_manifests(name.clone(), Vec::new(), false), Err(RegistryError::EmptyBatch));
+        assert_eq!(contract.set_default_content_type("".to_string()), Err(RegistryError::EmptyContentType));
+
+        publish_versions(&mut contract, &name, &["0.0.1"]);
+        assert_eq!(
+            contract.merge_packages(name.clone(), name.clone(), MergeStrategy::Skip),
+            Err(RegistryError::SelfMerge)
+        );
+        assert_eq!(
+            contract.respond_challenge(name.clone(), author.clone(), from_hex(CHALLENGE_SIGNATURE)),
+            Err(RegistryError::NoPendingChallenge)
+        );
+        assert_eq!(
+            contract.challenge_attestor("alice_near".parse().unwrap(), author, name),
+            Err(RegistryError::AttestorNotFound)
+        );
+    }
+
+    #[test]
+    fn error_messages_match_previous_reverts() {
+        assert_eq!(RegistryError::VersionExists.to_string(), "Version already exists for this package");
+        assert_eq!(RegistryError::PackageNotFound.to_string(), "Package name not found for given author");
+        assert_eq!(
+            RegistryError::InsufficientDeposit(10).to_string(),
+            "Attached deposit must cover 10 yoctoNEAR of storage"
+        );
+    }
+
+    #[test]
+    fn reject_publish_from_unregistered_contract() {
+        let mut contract = setup_contract_publisher();
+        contract.unregister_contract_publisher("org_near".parse().unwrap());
+        assert!(!contract.is_contract_publisher("org_near".parse().unwrap()));
+
+        testing_env!(get_contract_context("bob_near"));
+        contract.set_publish_allowlist(vec!["bob_near".parse().unwrap()]).unwrap();
+        let result = contract.create_manifest(
+            "test-package".to_string(),
+            "0.0.1".to_string(),
+            "ipfs".to_string(),
+            "QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB".to_string(),
+            Vec::new(),
+            None,
+            true,
+            None,
+            Vec::new()
+        );
+        assert_eq!(result, Err(RegistryError::UnregisteredContract));
+    }
+
+    #[test]
+    fn get_full_manifest_history() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+        let author = context.signer_account_id.clone();
+        let name = "test-package".to_string();
+
+        let mut contract = Contract::default();
+        contract.batch_create_manifests(
+            name.clone(),
+            vec![
+                ("0.0.1".to_string(), "ipfs".to_string(), "QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB".to_string()),
+                ("0.0.2".to_string(), "json".to_string(), "QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n".to_string()),
+                ("0.0.3".to_string(), "ipfs".to_string(), "bafybeicn7i3soqdgr7dwnrwytgq4zxy7a5jpkizrvhm5mv6bgjd32wm3q4".to_string())
+            ],
+            false
+        ).unwrap();
+
+        let history = contract.get_manifest_history(author.clone(), name.clone()).unwrap();
+        let expected = ["0.0.1", "0.0.2", "0.0.3"].iter()
+            .map(|v| contract.try_get_manifest(author.clone(), name.clone(), v.to_string()).unwrap())
+            .collect::<Vec<Manifest>>();
+
+        assert_eq!(history, expected);
+        assert_eq!(
+            history.iter().map(|m| (m.cid.as_str(), m.content_type.as_str())).collect::<Vec<_>>(),
+            vec![
+                ("QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB", "ipfs"),
+                ("QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n", "json"),
+                ("bafybeicn7i3soqdgr7dwnrwytgq4zxy7a5jpkizrvhm5mv6bgjd32wm3q4", "ipfs")
+            ]
+        );
+    }
+
+    #[test]
+    fn reject_duplicate_attestation() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+        let author = context.signer_account_id.clone();
+        let name = "test-package".to_string();
+        let cid = "QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n".to_string();
+
+        let mut contract = self_attesting_contract();
+        publish_versions(&mut contract, &name, &["0.0.1"]);
+
+        assert_eq!(contract.create_attestation(name.clone(), author.clone(), cid.clone(), None, None, false, None, String::new(), String::new(), 0), Ok(()));
+        assert_eq!(
+            contract.create_attestation(name.clone(), author.clone(), cid, Some("again".to_string()), None, false, None, String::new(), String::new(), 0),
+            Err(RegistryError::DuplicateAttestation)
+        );
+        assert_eq!(contract.count_attestations(author.clone(), name, author).unwrap(), 1);
+    }
+
+    #[test]
+    fn contract_attests_under_predecessor() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+        let author = context.signer_account_id.clone();
+        let name = "test-package".to_string();
+
+        let mut contract = Contract::default();
+        publish_versions(&mut contract, &name, &["0.0.1"]);
+
+        testing_env!(get_contract_context("bob_near"));
+        contract.create_attestation(
+            name.clone(),
+            author.clone(),
+            "QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n".to_string(),
+            None,
+            None,
+            true,
+            Some("ci-pipeline".to_string()),
+            String::new(),
+            String::new(),
+            0
+        ).unwrap();
+
+        let attestation = contract.get_attestation("org_near".parse().unwrap(), name.clone(), author.clone(), 0).unwrap();
+        assert_eq!(attestation.pubkey, None);
+        assert_eq!(attestation.identifier, Some("ci-pipeline".to_string()));
+        assert_eq!(contract.get_attestors(author, name), vec!["org_near".parse::<AccountId>().unwrap()]);
+    }
+
+    #[test]
+    fn contract_attestation_requires_identifier() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+        let author = context.signer_account_id.clone();
+        let name = "test-package".to_string();
+
+        let mut contract = Contract::default();
+        publish_versions(&mut contract, &name, &["0.0.1"]);
+
+        testing_env!(get_contract_context("bob_near"));
+        let result = contract.create_attestation(
+            name,
+            author,
+            "QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n".to_string(),
+            None,
+            None,
+            true,
+            None,
+            String::new(),
+            String::new(),
+            0
+        );
+        assert_eq!(result, Err(RegistryError::MissingAttestorIdentifier));
+    }
+
+    #[test]
+    fn contract_attestation_requires_cross_contract_call() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+        let author = context.signer_account_id.clone();
+        let name = "test-package".to_string();
+
+        let mut contract = Contract::default();
+        publish_versions(&mut contract, &name, &["0.0.1"]);
+
+        testing_env!(get_owner_context("alice_near"));
+        let result = contract.create_attestation(
+            name,
+            author,
+            "QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n".to_string(),
+            None,
+            None,
+            true,
+            Some("ci-pipeline".to_string()),
+            String::new(),
+            String::new(),
+            0
+        );
+        assert_eq!(result, Err(RegistryError::NotCalledByContract));
+    }
+
+    #[test]
+    fn contract_revokes_own_attestation() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+        let author = context.signer_account_id.clone();
+        let name = "test-package".to_string();
+
+        let mut contract = Contract::default();
+        publish_versions(&mut contract, &name, &["0.0.1"]);
+
+        testing_env!(get_contract_context("bob_near"));
+        contract.create_attestation(
+            name.clone(),
+            author.clone(),
+            "QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n".to_string(),
+            None,
+            None,
+            true,
+            Some("ci-pipeline".to_string()),
+            String::new(),
+            String::new(),
+            0
+        ).unwrap();
+
+        contract.revoke_attestation(name.clone(), author.clone(), 0, true).unwrap();
+        assert_eq!(contract.count_attestations("org_near".parse().unwrap(), name, author).unwrap(), 0);
+    }
+
+    #[test]
+    fn find_authors_across_accounts() {
+        let name = "test-package".to_string();
+        testing_env!(get_owner_context("alice_near"));
+        let mut contract = Contract::default();
+        publish_versions(&mut contract, &name, &["0.0.1"]);
+        testing_env!(get_owner_context("bob_near"));
+        publish_versions(&mut contract, &name, &["0.0.1"]);
+
+        assert_eq!(
+            contract.find_authors(name.clone()),
+            vec!["alice_near".parse::<AccountId>().unwrap(), "bob_near".parse::<AccountId>().unwrap()]
+        );
+        assert!(contract.find_authors("unknown-package".to_string()).is_empty());
+
+        contract.remove_package(name.clone()).unwrap();
+        assert_eq!(contract.find_authors(name), vec!["alice_near".parse::<AccountId>().unwrap()]);
+    }
+
+    #[test]
+    fn attestation_metadata_round_trips() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+        let author = context.signer_account_id.clone();
+        let name = "test-package".to_string();
+
+        let mut contract = self_attesting_contract();
+        publish_versions(&mut contract, &name, &["0.0.1"]);
+        contract.create_attestation(
+            name.clone(),
+            author.clone(),
+            "QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n".to_string(),
+            None,
+            None,
+            false,
+            None,
+            "security-audit".to_string(),
+            "1.0.0".to_string(),
+            0
+        ).unwrap();
+
+        let attestation = contract.get_attestation(author.clone(), name, author, 0).unwrap();
+        assert_eq!(attestation.claim_type, "security-audit");
+        assert_eq!(attestation.schema_version, "1.0.0");
+    }
+
+    #[test]
+    fn filter_attestations_by_pubkey() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+        let author = context.signer_account_id.clone();
+        let name = "test-package".to_string();
+
+        let mut contract = self_attesting_contract();
+        publish_versions(&mut contract, &name, &["0.0.1"]);
+        attest_times(&mut contract, author.clone(), &name, 2);
+
+        testing_env!(get_challenge_context());
+        contract.create_attestation(
+            name.clone(),
+            author.clone(),
+            "QmT78zSuBmuS4z925WZfrqQ1qHaJ56DQaTfyMUF7F8ff5o".to_string(),
+            None,
+            None,
+            false,
+            None,
+            String::new(),
+            String::new(),
+            0
+        ).unwrap();
+
+        let rotated = contract.get_attestations_by_pubkey(
+            author.clone(),
+            name.clone(),
+            author.clone(),
+            CHALLENGE_PK.parse().unwrap()
+        ).unwrap();
+        assert_eq!(rotated.len(), 1);
+        assert_eq!(rotated[0].cid, "QmT78zSuBmuS4z925WZfrqQ1qHaJ56DQaTfyMUF7F8ff5o".to_string());
+
+        let original = contract.get_attestations_by_pubkey(author.clone(), name, author, context.signer_account_pk).unwrap();
+        assert_eq!(
+            original.iter().map(|a| a.cid.as_str()).collect::<Vec<_>>(),
+            vec!["QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB", "QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n"]
+        );
+    }
+
+    #[test]
+    fn reject_self_attestation_by_default() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+        let author = context.signer_account_id.clone();
+        let name = "test-package".to_string();
+
+        let mut contract = Contract::default();
+        publish_versions(&mut contract, &name, &["0.0.1"]);
+
+        let result = contract.create_attestation(
+            name,
+            author,
+            "QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n".to_string(),
+            None,
+            None,
+            false,
+            None,
+            String::new(),
+            String::new(),
+            0
+        );
+        assert_eq!(result, Err(RegistryError::SelfAttestation));
+    }
+
+    #[test]
+    fn allow_self_attestation_when_owner_enables_it() {
+        testing_env!(get_owner_context("owner_near"));
+        let author: AccountId = "owner_near".parse().unwrap();
+        let name = "test-package".to_string();
+
+        let mut contract = Contract::new(author.clone());
+        contract.set_self_attestation_allowed(true);
+        assert!(contract.is_self_attestation_allowed());
+        publish_versions(&mut contract, &name, &["0.0.1"]);
+
+        contract.create_attestation(
+            name.clone(),
+            author.clone(),
+            "QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n".to_string(),
+            None,
+            None,
+            false,
+            None,
+            String::new(),
+            String::new(),
+            0
+        ).unwrap();
+        assert_eq!(contract.count_attestations(author.clone(), name, author).unwrap(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the owner can call this method")]
+    fn non_owner_cannot_allow_self_attestation() {
+        testing_env!(get_owner_context("owner_near"));
+        let mut contract = Contract::new("owner_near".parse().unwrap());
+
+        testing_env!(get_owner_context("bob_near"));
+        contract.set_self_attestation_allowed(true);
+    }
+
+    #[test]
+    fn lookup_manifest_by_cid() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+        let author = context.signer_account_id.clone();
+        let name = "test-package".to_string();
+        let cid = "QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB".to_string();
+        let updated = "QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n".to_string();
+
+        let mut contract = Contract::default();
+        publish_versions(&mut contract, &name, &["0.0.1", "0.0.2"]);
+        assert_eq!(contract.lookup_by_cid(cid.clone()), Some((author.clone(), name.clone(), "0.0.1".to_string())));
+
+        contract.update_manifest(name.clone(), "0.0.1".to_string(), "ipfs".to_string(), updated.clone()).unwrap();
+        assert_eq!(contract.lookup_by_cid(updated), Some((author.clone(), name.clone(), "0.0.1".to_string())));
+        assert_eq!(contract.lookup_by_cid(cid.clone()), Some((author, name.clone(), "0.0.2".to_string())));
+
+        contract.delete_manifest(name, "0.0.2".to_string()).unwrap();
+        assert_eq!(contract.lookup_by_cid(cid), None);
+    }
+
+    #[test]
+    fn lookup_unknown_cid() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+
+        let mut contract = Contract::default();
+        publish_versions(&mut contract, "test-package", &["0.0.1"]);
+
+        assert_eq!(contract.lookup_by_cid("QmT78zSuBmuS4z925WZfrqQ1qHaJ56DQaTfyMUF7F8ff5o".to_string()), None);
+    }
+
+    #[test]
+    fn reject_versions_past_limit() {
+        testing_env!(get_owner_context("owner_near"));
+        let name = "test-package";
+
+        let mut contract = Contract::new("owner_near".parse().unwrap());
+        contract.set_max_versions_per_package(2);
+        publish_versions(&mut contract, name, &["0.0.1", "0.0.2"]);
+
+        assert_eq!(try_publish(&mut contract, name, "0.0.3"), Err(RegistryError::VersionLimitReached));
+        assert_eq!(contract.total_manifests(), 2);
+    }
+
+    #[test]
+    fn reject_batch_past_version_limit() {
+        testing_env!(get_owner_context("owner_near"));
+        let name = "test-package".to_string();
+
+        let mut contract = Contract::new("owner_near".parse().unwrap());
+        contract.set_max_versions_per_package(2);
+        publish_versions(&mut contract, &name, &["0.0.1"]);
+
+        let result = contract.batch_create_manifests(
+            name,
+            vec![
+                ("0.0.2".to_string(), "ipfs".to_string(), "QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n".to_string()),
+                ("0.0.3".to_string(), "ipfs".to_string(), "QmT78zSuBmuS4z925WZfrqQ1qHaJ56DQaTfyMUF7F8ff5o".to_string())
+            ],
+            false
+        );
+        assert_eq!(result, Err(RegistryError::VersionLimitReached));
+    }
+
+    #[test]
+    fn reject_merge_past_version_limit() {
+        testing_env!(get_owner_context("owner_near"));
+
+        let mut contract = Contract::new("owner_near".parse().unwrap());
+        contract.set_max_versions_per_package(2);
+        setup_merge(&mut contract);
+
+        let result = contract.merge_packages("from-package".to_string(), "into-package".to_string(), MergeStrategy::Skip);
+        assert_eq!(result, Err(RegistryError::VersionLimitReached));
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the owner can call this method")]
+    fn non_owner_cannot_set_version_limit() {
+        testing_env!(get_owner_context("owner_near"));
+        let mut contract = Contract::new("owner_near".parse().unwrap());
+
+        testing_env!(get_owner_context("bob_near"));
+        contract.set_max_versions_per_package(2);
+    }
+
+    #[test]
+    fn export_packages_across_pages() {
+        testing_env!(get_owner_context("alice_near"));
+        let mut contract = Contract::default();
+        publish_versions(&mut contract, "package-a", &["0.0.1", "0.0.2"]);
+        publish_versions(&mut contract, "package-b", &["0.0.1"]);
+
+        testing_env!(get_owner_context("bob_near"));
+        publish_versions(&mut contract, "package-a", &["0.0.1"]);
+
+        let first = contract.export_packages(0, 2);
+        let second = contract.export_packages(2, 2);
+        let exported = first.iter().chain(second.iter())
+            .map(|(author, name, versions)| (author.to_string(), name.as_str(), versions.len()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(first.len(), 2);
+        assert_eq!(
+            exported,
+            vec![
+                ("alice_near".to_string(), "package-a", 2),
+                ("alice_near".to_string(), "package-b", 1),
+                ("bob_near".to_string(), "package-a", 1)
+            ]
+        );
+        assert!(contract.export_packages(3, 2).is_empty());
+        assert_eq!(contract.package_index_len(), 3);
+    }
+
+    #[test]
+    fn export_skips_removed_packages() {
+        testing_env!(get_owner_context("alice_near"));
+        let mut contract = Contract::default();
+        publish_versions(&mut contract, "package-a", &["0.0.1"]);
+        publish_versions(&mut contract, "package-b", &["0.0.1"]);
+
+        contract.remove_package("package-a".to_string()).unwrap();
+        publish_versions(&mut contract, "package-a", &["0.0.2"]);
+        contract.remove_package("package-b".to_string()).unwrap();
+
+        let exported = contract.export_packages(0, 10);
+        assert_eq!(exported.len(), 1);
+        assert_eq!(exported[0].1, "package-a".to_string());
+        assert_eq!(exported[0].2[0].version, "0.0.2".to_string());
+    }
+
+    #[test]
+    fn export_pages_survive_removing_an_earlier_package() {
+        testing_env!(get_owner_context("alice_near"));
+        let mut contract = Contract::default();
+        for name in ["package-a", "package-b", "package-c", "package-d"] {
+            publish_versions(&mut contract, name, &["0.0.1"]);
+        }
+
+        let first = contract.export_packages(0, 2);
+        assert_eq!(first[0].1, "package-a".to_string());
+        // Removing a package already paged over must not shift the next page onto package-d
+        contract.remove_package("package-a".to_string()).unwrap();
+        contract.remove_package("package-b".to_string()).unwrap();
+
+        let second = contract.export_packages(2, 2);
+        let names = second.iter().map(|(_, name, _)| name.as_str()).collect::<Vec<&str>>();
+        assert_eq!(names, vec!["package-c", "package-d"]);
+        assert_eq!(contract.package_index_len(), 4);
+    }
+
+    #[test]
+    fn search_all_pages_through_matches() {
+        testing_env!(get_owner_context("alice_near"));
+        let mut contract = Contract::default();
+        publish_versions(&mut contract, "near-sdk", &["0.0.1"]);
+        publish_versions(&mut contract, "borsh", &["0.0.1"]);
+        publish_versions(&mut contract, "near-api", &["0.0.1"]);
+
+        testing_env!(get_owner_context("bob_near"));
+        publish_versions(&mut contract, "near-sdk", &["0.0.1"]);
+        publish_versions(&mut contract, "nearby", &["0.0.1"]);
+        publish_versions(&mut contract, "near-cli", &["0.0.1"]);
+        contract.remove_package("nearby".to_string()).unwrap();
+
+        let mut matches = Vec::new();
+        let mut from_index = 0;
+        while from_index < contract.package_index_len() {
+            let page = contract.search_all("NEAR-".to_string(), from_index, 2);
+            assert!(page.len() <= 2);
+            matches.extend(page.into_iter().map(|(author, name)| (author.to_string(), name)));
+            from_index += 2;
+        }
+
+        assert_eq!(
+            matches,
+            vec![
+                ("alice_near".to_string(), "near-sdk".to_string()),
+                ("alice_near".to_string(), "near-api".to_string()),
+                ("bob_near".to_string(), "near-sdk".to_string()),
+                ("bob_near".to_string(), "near-cli".to_string())
+            ]
+        );
+        // The removed package matches the prefix but is skipped
+        assert_eq!(contract.search_all("nearb".to_string(), 0, 10), Vec::new());
+        assert_eq!(contract.search_all("borsh".to_string(), 0, 10).len(), 1);
+        assert!(contract.search_all("near".to_string(), 6, 10).is_empty());
+    }
+
+    #[test]
+    fn record_downloads() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+        let author = context.signer_account_id.clone();
+        let name = "test-package".to_string();
+
+        let mut contract = Contract::default();
+        publish_versions(&mut contract, &name, &["0.0.1", "0.0.2"]);
+        let root = contract.get_package_root(author.clone(), name.clone()).unwrap();
+
+        for _ in 0..3 {
+            contract.record_download(author.clone(), name.clone(), "0.0.1".to_string()).unwrap();
+        }
+
+        let downloads = |contract: &Contract, version: &str| {
+            return contract.try_get_manifest(author.clone(), name.clone(), version.to_string()).unwrap().downloads;
+        };
+        assert_eq!(downloads(&contract, "0.0.1"), 3);
+        assert_eq!(downloads(&contract, "0.0.2"), 0);
+        assert_eq!(contract.get_package_root(author.clone(), name.clone()).unwrap(), root);
+
+        contract.update_manifest(
+            name.clone(),
+            "0.0.1".to_string(),
+            "ipfs".to_string(),
+            "QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n".to_string()
+        ).unwrap();
+        assert_eq!(downloads(&contract, "0.0.1"), 3);
+    }
+
+    #[test]
+    fn record_download_for_unknown_version() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+        let name = "test-package".to_string();
+
+        let mut contract = Contract::default();
+        publish_versions(&mut contract, &name, &["0.0.1"]);
+
+        let result = contract.record_download(context.signer_account_id.clone(), name, "0.0.2".to_string());
+        assert_eq!(result, Err(RegistryError::VersionNotFound));
+    }
+
+    fn publish_with_content_type(contract: &mut Contract, name: &str, version: &str, content_type: &str) -> Result<(), RegistryError> {
+        return contract.create_manifest(
+            name.to_string(),
+            version.to_string(),
+            content_type.to_string(),
+            "QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB".to_string(),
+            Vec::new(),
+            None,
+            false,
+            None,
+            Vec::new()
+        );
+    }
+
+    #[test]
+    fn normalize_known_content_type() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+        let name = "test-package";
+
+        let mut contract = Contract::default();
+        publish_with_content_type(&mut contract, name, "0.0.1", "IPFS").unwrap();
+
+        let manifest = contract.try_get_manifest(context.signer_account_id.clone(), name.to_string(), "0.0.1".to_string());
+        assert_eq!(manifest.unwrap().content_type, "ipfs".to_string());
+        assert_eq!(ContentType::parse("Hyperfiles"), Some(ContentType::Hyperfiles));
+    }
+
+    #[test]
+    fn keep_other_content_type() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+        let name = "test-package";
+
+        let mut contract = Contract::default();
+        publish_with_content_type(&mut contract, name, "0.0.1", "application/wasm").unwrap();
+
+        let manifest = contract.try_get_manifest(context.signer_account_id.clone(), name.to_string(), "0.0.1".to_string());
+        assert_eq!(manifest.unwrap().content_type, "application/wasm".to_string());
+        assert_eq!(ContentType::parse("application/wasm"), Some(ContentType::Other("application/wasm".to_string())));
+    }
+
+    #[test]
+    fn reject_invalid_content_types() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+        let name = "test-package";
+
+        let mut contract = Contract::default();
+        assert_eq!(publish_with_content_type(&mut contract, name, "0.0.1", "ipfs "), Err(RegistryError::InvalidContentType));
+
+        publish_with_content_type(&mut contract, name, "0.0.1", "ipfs").unwrap();
+        let result = contract.update_manifest(
+            name.to_string(),
+            "0.0.1".to_string(),
+            "".to_string(),
+            "QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n".to_string()
+        );
+        assert_eq!(result, Err(RegistryError::InvalidContentType));
+    }
+
+    // org_near registers the challenge key and allows bob_near to publish through it
+    fn setup_signed_contract_publisher() -> Contract {
+        let mut contract = setup_contract_publisher();
+        testing_env!(get_owner_context("org_near"));
+        contract.register_author_key(CHALLENGE_PK.parse().unwrap()).unwrap();
+        contract.set_publish_allowlist(vec!["bob_near".parse().unwrap()]).unwrap();
+
+        testing_env!(get_contract_context("bob_near"));
+        return contract;
+    }
+
+    // Signature by the challenge key over the domain prefix and the borsh encoded
+    // (org_near, test-package, 0.0.1, QmPK1s...) release
+    const AUTHOR_SIGNATURE: &str = "bbb4e9209a927067617a19d274e7ba434f302b78e680a10dd02f8dc772006d5290f63c51071d955b8d877c69bac69704b719a1ef7cac85e1fb3de36ce37dc900";
+
+    fn publish_signed(contract: &mut Contract, version: &str, signature: Option<Vec<u8>>) -> Result<(), RegistryError> {
+        return contract.create_manifest(
+            "test-package".to_string(),
+            version.to_string(),
+            "ipfs".to_string(),
+            "QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB".to_string(),
+            Vec::new(),
+            None,
+            true,
+            signature,
+            Vec::new()
+        );
+    }
+
+    #[test]
+    fn publish_with_author_signature() {
+        let mut contract = setup_signed_contract_publisher();
+
+        publish_signed(&mut contract, "0.0.1", Some(from_hex(AUTHOR_SIGNATURE))).unwrap();
+        assert_eq!(
+            contract.get_latest_manifest("org_near".parse().unwrap(), "test-package".to_string()).unwrap(),
+            "QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB".to_string()
+        );
+    }
+
+    #[test]
+    fn reject_forged_author_signature() {
+        let mut contract = setup_signed_contract_publisher();
+
+        let mut signature = from_hex(AUTHOR_SIGNATURE);
+        signature[10] ^= 0xff;
+        assert_eq!(publish_signed(&mut contract, "0.0.1", Some(signature)), Err(RegistryError::InvalidManifestSignature));
+        assert_eq!(publish_signed(&mut contract, "0.0.1", None), Err(RegistryError::MissingManifestSignature));
+    }
+
+    #[test]
+    fn reject_replayed_author_signature() {
+        let mut contract = setup_signed_contract_publisher();
+
+        // A signature over the bare cid is no longer accepted
+        let result = publish_signed(&mut contract, "0.0.1", Some(from_hex(MANIFEST_SIGNATURE)));
+        assert_eq!(result, Err(RegistryError::InvalidManifestSignature));
+
+        // The signature for 0.0.1 cannot publish the same cid as another version
+        publish_signed(&mut contract, "0.0.1", Some(from_hex(AUTHOR_SIGNATURE))).unwrap();
+        let result = publish_signed(&mut contract, "0.0.2", Some(from_hex(AUTHOR_SIGNATURE)));
+        assert_eq!(result, Err(RegistryError::InvalidManifestSignature));
+    }
+
+    #[test]
+    fn reject_author_key_from_relaying_contract() {
+        let mut contract = setup_contract_publisher();
+        testing_env!(get_contract_context("bob_near"));
+
+        let result = contract.register_author_key(CHALLENGE_PK.parse().unwrap());
+        assert_eq!(result, Err(RegistryError::IndirectKeyRegistration));
+        assert_eq!(contract.get_author_key("org_near".parse().unwrap()), None);
+    }
+
+    #[test]
+    fn count_attestations_across_author_packages() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+        let author = context.signer_account_id.clone();
+
+        let mut contract = self_attesting_contract();
+        publish_versions(&mut contract, "first-package", &["0.0.1"]);
+        publish_versions(&mut contract, "second-package", &["0.0.1"]);
+        publish_versions(&mut contract, "third-package", &["0.0.1"]);
+        assert_eq!(contract.total_attestations_for_author(author.clone()), 0);
+
+        attest_times(&mut contract, author.clone(), "first-package", 2);
+        attest_times(&mut contract, author.clone(), "second-package", 3);
+        assert_eq!(contract.total_attestations_for_author(author.clone()), 5);
+
+        contract.revoke_attestation("second-package".to_string(), author.clone(), 0, false).unwrap();
+        assert_eq!(contract.total_attestations_for_author(author), 4);
+        assert_eq!(contract.total_attestations_for_author("alice_near".parse().unwrap()), 0);
+    }
+
+    #[test]
+    fn has_attested_with_matching_key() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+        let author = context.signer_account_id.clone();
+        let name = "test-package".to_string();
+
+        let mut contract = self_attesting_contract();
+        publish_versions(&mut contract, &name, &["0.0.1"]);
+        attest_times(&mut contract, author.clone(), &name, 1);
+
+        assert!(contract.has_attested(author.clone(), name, author, context.signer_account_pk));
+    }
+
+    #[test]
+    fn has_not_attested_with_other_key() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+        let author = context.signer_account_id.clone();
+        let name = "test-package".to_string();
+
+        let mut contract = self_attesting_contract();
+        publish_versions(&mut contract, &name, &["0.0.1"]);
+        attest_times(&mut contract, author.clone(), &name, 1);
+
+        assert!(!contract.has_attested(author.clone(), name, author, CHALLENGE_PK.parse().unwrap()));
+    }
+
+    #[test]
+    fn has_not_attested_when_absent() {
+        let context = get_context(true);
+        testing_env!(context.clone());
+        let author = context.signer_account_id.clone();
+        let contract = Contract::default();
+
+        assert!(!contract.has_attested(
+            "alice_near".parse().unwrap(),
+            "test-package".to_string(),
+            author,
+            context.signer_account_pk
+        ));
+    }
+
+    #[test]
+    fn set_and_move_tag() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+        let author = context.signer_account_id.clone();
+        let name = "test-package".to_string();
+        let tag = "latest".to_string();
+
+        let mut contract = Contract::default();
+        publish_versions(&mut contract, &name, &["0.0.1", "0.0.2"]);
+        assert_eq!(contract.get_by_tag(author.clone(), name.clone(), tag.clone()), None);
+
+        contract.set_tag(name.clone(), tag.clone(), "0.0.1".to_string()).unwrap();
+        assert_eq!(contract.get_by_tag(author.clone(), name.clone(), tag.clone()).unwrap().version, "0.0.1".to_string());
+
+        contract.set_tag(name.clone(), tag.clone(), "0.0.2".to_string()).unwrap();
+        assert_eq!(contract.get_by_tag(author.clone(), name.clone(), tag.clone()).unwrap().version, "0.0.2".to_string());
+        assert_eq!(contract.get_by_tag(author, name, "beta".to_string()), None);
+    }
+
+    #[test]
+    fn list_tags_of_package() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+        let author = context.signer_account_id.clone();
+        let name = "test-package".to_string();
+
+        let mut contract = Contract::default();
+        publish_versions(&mut contract, &name, &["0.0.1", "0.0.2", "0.0.3"]);
+        publish_versions(&mut contract, "other-package", &["1.0.0"]);
+        assert!(contract.list_dist_tags(author.clone(), name.clone()).is_empty());
+
+        contract.set_tag(name.clone(), "latest".to_string(), "0.0.2".to_string()).unwrap();
+        contract.set_tag(name.clone(), "beta".to_string(), "0.0.3".to_string()).unwrap();
+        contract.set_tag(name.clone(), "lts".to_string(), "0.0.1".to_string()).unwrap();
+        contract.set_tag(name.clone(), "latest".to_string(), "0.0.3".to_string()).unwrap();
+        contract.set_tag("other-package".to_string(), "latest".to_string(), "1.0.0".to_string()).unwrap();
+
+        let mut tags = contract.list_dist_tags(author, name);
+        tags.sort();
+        assert_eq!(tags, vec![
+            ("beta".to_string(), "0.0.3".to_string()),
+            ("latest".to_string(), "0.0.3".to_string()),
+            ("lts".to_string(), "0.0.1".to_string())
+        ]);
+    }
+
+    #[test]
+    fn remove_tag() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+        let author = context.signer_account_id.clone();
+        let name = "test-package".to_string();
+
+        let mut contract = Contract::default();
+        publish_versions(&mut contract, &name, &["0.0.1", "0.0.2"]);
+        contract.set_tag(name.clone(), "latest".to_string(), "0.0.2".to_string()).unwrap();
+        contract.set_tag(name.clone(), "beta".to_string(), "0.0.1".to_string()).unwrap();
+
+        contract.remove_dist_tag(name.clone(), "latest".to_string()).unwrap();
+        assert_eq!(contract.get_by_tag(author.clone(), name.clone(), "latest".to_string()), None);
+        assert_eq!(contract.list_dist_tags(author.clone(), name.clone()), vec![("beta".to_string(), "0.0.1".to_string())]);
+        assert_eq!(contract.list_versions(author, name.clone()).unwrap(), vec!["0.0.1", "0.0.2"]);
+
+        let result = contract.remove_dist_tag(name, "latest".to_string());
+        assert_eq!(result, Err(RegistryError::TagNotFound));
+    }
+
+    #[test]
+    fn removed_package_drops_its_tags() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+        let author = context.signer_account_id.clone();
         let name = "test-package".to_string();
-        let version = "0.0.1".to_string();
-        let content_type = "ipfs".to_string();
 
         let mut contract = Contract::default();
-        contract.create_manifest(
-            name.clone(),
-            version.clone(),
-            content_type.clone(),
-            cid.clone(),
-            Vec::new(),
-            false
-        );
-        assert_eq!(
-            contract.get_manifest(context.signer_account_id.clone(), name.clone(), version.clone()),
-            cid.clone()
-        );
+        publish_versions(&mut contract, &name, &["0.0.1"]);
+        contract.set_tag(name.clone(), "latest".to_string(), "0.0.1".to_string()).unwrap();
+        contract.remove_package(name.clone()).unwrap();
+
+        // Republishing the same version must not bring the old tag back
+        publish_versions(&mut contract, &name, &["0.0.1"]);
+        assert_eq!(contract.get_by_tag(author.clone(), name.clone(), "latest".to_string()), None);
+        assert!(contract.list_dist_tags(author, name).is_empty());
     }
 
     #[test]
-    fn set_multiple_manifest() {
+    fn merged_and_transferred_packages_drop_their_tags() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+        let author = context.signer_account_id.clone();
+        let new_owner: AccountId = "alice_near".parse().unwrap();
+
+        let mut contract = Contract::default();
+        setup_merge(&mut contract);
+        contract.set_tag("from-package".to_string(), "latest".to_string(), "0.0.1".to_string()).unwrap();
+        contract.merge_packages("from-package".to_string(), "into-package".to_string(), MergeStrategy::Skip).unwrap();
+        assert!(contract.list_dist_tags(author.clone(), "from-package".to_string()).is_empty());
+
+        contract.set_tag("into-package".to_string(), "latest".to_string(), "0.0.3".to_string()).unwrap();
+        contract.offer_package_transfer("into-package".to_string(), new_owner.clone()).unwrap();
+        testing_env!(get_owner_context("alice_near"));
+        contract.accept_package_transfer(author.clone(), "into-package".to_string()).unwrap();
+        assert!(contract.list_dist_tags(author, "into-package".to_string()).is_empty());
+        assert!(contract.list_dist_tags(new_owner, "into-package".to_string()).is_empty());
+    }
+
+    #[test]
+    fn reject_tag_for_unknown_version() {
         let context = get_context(false);
         testing_env!(context.clone());
-        let cid = "QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB".to_string();
         let name = "test-package".to_string();
-        let version = "0.0.1".to_string();
-        let content_type = "ipfs".to_string();
 
         let mut contract = Contract::default();
-        contract.create_manifest(
-            name.clone(),
-            version.clone(),
-            content_type.clone(),
-            cid.clone(),
-            Vec::new(),
-            false
-        );
+        publish_versions(&mut contract, &name, &["0.0.1"]);
+
+        let result = contract.set_tag(name, "latest".to_string(), "0.0.2".to_string());
+        assert_eq!(result, Err(RegistryError::VersionNotFound));
+    }
+
+    #[test]
+    fn reject_tag_too_long() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+        let name = "test-package".to_string();
+
+        let mut contract = Contract::default();
+        publish_versions(&mut contract, &name, &["0.0.1"]);
 
+        let result = contract.set_tag(name, "a".repeat(MAX_TAG_LENGTH + 1), "0.0.1".to_string());
+        assert_eq!(result, Err(RegistryError::TagTooLong));
+    }
+
+    #[test]
+    fn get_latest_manifests_for_several_packages() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+        let author = context.signer_account_id.clone();
+
+        let mut contract = Contract::default();
+        publish_versions(&mut contract, "first-package", &["0.0.1"]);
         contract.create_manifest(
-            "new_package".to_string(),
-            version.clone(),
-            content_type.clone(),
-            cid.clone(),
+            "second-package".to_string(),
+            "0.0.1".to_string(),
+            "ipfs".to_string(),
+            "QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n".to_string(),
             Vec::new(),
-            false
+            None,
+            false,
+            None,
+            Vec::new()
+        ).unwrap();
+
+        let names = ["first-package", "missing-package", "second-package"].map(String::from).to_vec();
+        assert_eq!(
+            contract.get_latest_manifests(author, names),
+            vec![
+                ("first-package".to_string(), Some("QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB".to_string())),
+                ("missing-package".to_string(), None),
+                ("second-package".to_string(), Some("QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n".to_string()))
+            ]
+        );
+        assert_eq!(
+            contract.get_latest_manifests("alice_near".parse().unwrap(), vec!["first-package".to_string()]),
+            vec![("first-package".to_string(), None)]
         );
+    }
 
-        contract.create_manifest(
-            name.clone(),
-            "0.0.2".to_string(),
-            content_type.clone(),
-            cid.clone(),
+    fn publish_with_dependencies(contract: &mut Contract, dependencies: Vec<Dependency>) -> Result<(), RegistryError> {
+        return contract.create_manifest(
+            "test-package".to_string(),
+            "0.0.1".to_string(),
+            "ipfs".to_string(),
+            "QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB".to_string(),
             Vec::new(),
-            false
+            None,
+            false,
+            None,
+            dependencies
         );
+    }
+
+    #[test]
+    fn dependencies_round_trip() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+        let author = context.signer_account_id.clone();
+        let dependencies = vec![
+            ("alice_near".parse().unwrap(), "first-package".to_string(), "^1.2.0".to_string()),
+            ("org_near".parse().unwrap(), "second-package".to_string(), "0.3.1".to_string())
+        ];
+
+        let mut contract = Contract::default();
+        publish_with_dependencies(&mut contract, dependencies.clone()).unwrap();
+
+        let manifest = contract.try_get_manifest(author.clone(), "test-package".to_string(), "0.0.1".to_string());
+        assert_eq!(manifest.unwrap().dependencies, dependencies);
+        assert_eq!(contract.get_latest_manifest_full(author, "test-package".to_string()).unwrap().dependencies, dependencies);
+    }
+
+    #[test]
+    fn reject_too_many_dependencies() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+        let dependencies = (0..=MAX_DEPENDENCIES)
+            .map(|i| ("alice_near".parse().unwrap(), format!("package-{i}"), "1.0.0".to_string()))
+            .collect::<Vec<Dependency>>();
+
+        let mut contract = Contract::default();
+        assert_eq!(publish_with_dependencies(&mut contract, dependencies), Err(RegistryError::TooManyDependencies));
+    }
+
+    fn publish_range_versions(contract: &mut Contract, name: &str) {
+        let cids = [
+            "QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB",
+            "QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n",
+            "bafybeicn7i3soqdgr7dwnrwytgq4zxy7a5jpkizrvhm5mv6bgjd32wm3q4",
+            "QmT78zSuBmuS4z925WZfrqQ1qHaJ56DQaTfyMUF7F8ff5o",
+            "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG"
+        ];
+
+        for (version, cid) in ["0.1.0", "0.1.4", "0.1.2", "0.2.0", "0.1.5-beta.1"].iter().zip(cids) {
+            contract.create_manifest(
+                name.to_string(),
+                version.to_string(),
+                "ipfs".to_string(),
+                cid.to_string(),
+                Vec::new(),
+                None,
+                false,
+                None,
+                Vec::new()
+            ).unwrap();
+        }
+    }
+
+    #[test]
+    fn resolve_caret_range_to_highest_patch() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+        let author = context.signer_account_id.clone();
+        let name = "test-package".to_string();
+
+        let mut contract = Contract::default();
+        publish_range_versions(&mut contract, &name);
 
         assert_eq!(
-            contract.get_manifest(context.signer_account_id.clone(), name.clone(), version.clone()),
-            cid.clone()
+            contract.resolve_range(author.clone(), name.clone(), "^0.1".to_string()),
+            Some("QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n".to_string())
         );
-
         assert_eq!(
-            contract.get_manifest(context.signer_account_id.clone(), name.clone(), "0.0.2".to_string()),
-            cid.clone()
+            contract.resolve_range(author.clone(), name.clone(), ">=0.1.3".to_string()),
+            Some("QmT78zSuBmuS4z925WZfrqQ1qHaJ56DQaTfyMUF7F8ff5o".to_string())
         );
-
         assert_eq!(
-            contract.get_manifest(context.signer_account_id.clone(), "new_package".to_string(), version.to_string()),
-            cid.clone()
+            contract.resolve_range(author, name, "0.1.2".to_string()),
+            Some("bafybeicn7i3soqdgr7dwnrwytgq4zxy7a5jpkizrvhm5mv6bgjd32wm3q4".to_string())
         );
     }
 
     #[test]
-    fn update_existing_manifest() {
+    fn resolve_range_without_match() {
         let context = get_context(false);
         testing_env!(context.clone());
-        let cid = "QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB".to_string();
+        let author = context.signer_account_id.clone();
         let name = "test-package".to_string();
-        let version = "0.0.1".to_string();
-        let content_type = "ipfs".to_string();
 
         let mut contract = Contract::default();
-        contract.create_manifest(
-            name.clone(),
-            version.clone(),
-            content_type.clone(),
-            cid.clone(),
-            Vec::new(),
-            false
-        );
+        publish_range_versions(&mut contract, &name);
 
-        let new_cid = "QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n".to_string();
+        assert_eq!(contract.resolve_range(author.clone(), name.clone(), "^1.0.0".to_string()), None);
+        assert_eq!(contract.resolve_range(author.clone(), name.clone(), "~0.3".to_string()), None);
+        assert_eq!(contract.resolve_range(author, name, "not-a-range".to_string()), None);
+    }
 
-        contract.update_manifest(
-            name.to_string(),
-            version.to_string(),
-            "hyperfiles".to_string(),
-            new_cid.clone()
+    #[test]
+    fn resolve_range_at_largest_version() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+        let author = context.signer_account_id.clone();
+        let name = "test-package".to_string();
+        let largest = format!("{}.0.0", u64::MAX);
+
+        let mut contract = Contract::default();
+        publish_versions(&mut contract, &name, &[largest.as_str()]);
+
+        let cid = "QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB".to_string();
+        assert_eq!(contract.resolve_range(author.clone(), name.clone(), format!("^{largest}")), Some(cid.clone()));
+        assert_eq!(contract.resolve_range(author, name, format!("~{}", u64::MAX)), Some(cid));
+    }
+
+    #[test]
+    fn filter_expired_attestations() {
+        let context = VMContextBuilder::new()
+            .signer_account_id("bob_near".parse().unwrap())
+            .block_timestamp(1_000)
+            .attached_deposit(STORAGE_DEPOSIT)
+            .build();
+        testing_env!(context.clone());
+        let author = context.signer_account_id.clone();
+        let name = "test-package".to_string();
+
+        let mut contract = self_attesting_contract();
+        publish_versions(&mut contract, &name, &["0.0.1"]);
+
+        let cids = [
+            "QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB",
+            "QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n",
+            "QmT78zSuBmuS4z925WZfrqQ1qHaJ56DQaTfyMUF7F8ff5o"
+        ];
+        for (cid, expires_at) in cids.iter().zip([500, 2_000, 0]) {
+            contract.create_attestation(
+                name.clone(),
+                author.clone(),
+                cid.to_string(),
+                None,
+                None,
+                false,
+                None,
+                String::new(),
+                String::new(),
+                expires_at
+            ).unwrap();
+        }
+
+        let valid = contract.get_valid_attestations(author.clone(), name.clone(), author.clone()).unwrap();
+        assert_eq!(
+            valid.iter().map(|a| a.cid.as_str()).collect::<Vec<_>>(),
+            vec!["QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n", "QmT78zSuBmuS4z925WZfrqQ1qHaJ56DQaTfyMUF7F8ff5o"]
         );
+        assert_eq!(contract.get_attestations(author.clone(), name, author).unwrap().len(), 3);
+    }
+
+    fn setup_three_attestors() -> (Contract, AccountId, String) {
+        let context = get_context(false);
+        testing_env!(context.clone());
+        let author = context.signer_account_id.clone();
+        let name = "test-package".to_string();
+
+        let mut contract = Contract::default();
+        publish_versions(&mut contract, &name, &["0.0.1"]);
+
+        for attestor in ["alice_near", "carol_near", "dave_near"] {
+            testing_env!(get_owner_context(attestor));
+            let count = if attestor == "carol_near" { 2 } else { 1 };
+            attest_times(&mut contract, author.clone(), &name, count);
+        }
+
+        return (contract, author, name);
+    }
+
+    #[test]
+    fn aggregate_attestations_across_attestors() {
+        let (contract, author, name) = setup_three_attestors();
 
         assert_eq!(
-            contract.get_manifest(context.signer_account_id.clone(), name.clone(), version.to_string()),
-            new_cid.clone()
+            contract.get_all_attestations(author.clone(), name.clone()).iter().map(|a| a.cid.as_str()).collect::<Vec<_>>(),
+            vec![
+                "QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB",
+                "QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB",
+                "QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n",
+                "QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB"
+            ]
         );
+        assert!(contract.get_all_attestations(author, "unknown-package".to_string()).is_empty());
     }
 
+    #[test]
+    fn page_attestations_across_attestors() {
+        let (contract, author, name) = setup_three_attestors();
+
+        assert_eq!(contract.get_all_attestations_paged(author.clone(), name.clone(), 1, 1).len(), 2);
+        assert_eq!(contract.get_all_attestations_paged(author.clone(), name.clone(), 2, 5).len(), 1);
+        assert!(contract.get_all_attestations_paged(author, name, 3, 5).is_empty());
+    }
 
     #[test]
-    fn set_attestation() {
+    fn upsert_creates_then_updates() {
         let context = get_context(false);
         testing_env!(context.clone());
-        let cid = "QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB".to_string();
+        let author = context.signer_account_id.clone();
         let name = "test-package".to_string();
-        let version = "0.0.1".to_string();
-        let content_type = "ipfs".to_string();
+        let updated = "QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n".to_string();
 
         let mut contract = Contract::default();
-        contract.create_manifest(
+        contract.upsert_manifest(
             name.clone(),
-            version.clone(),
-            content_type.clone(),
-            cid.clone(),
-            Vec::new(),
+            "0.0.1".to_string(),
+            "ipfs".to_string(),
+            "QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB".to_string(),
             false,
-        );
+            None
+        ).unwrap();
+        assert_eq!(contract.list_versions(author.clone(), name.clone()).unwrap(), vec!["0.0.1".to_string()]);
+
+        contract.upsert_manifest(name.clone(), "0.0.1".to_string(), "ipfs".to_string(), updated.clone(), false, None).unwrap();
+        assert_eq!(contract.list_versions(author.clone(), name.clone()).unwrap(), vec!["0.0.1".to_string()]);
+        assert_eq!(contract.get_latest_manifest(author, name).unwrap(), updated);
+        assert_eq!(contract.total_manifests(), 1);
+    }
+
+    #[test]
+    fn block_and_unblock_cid() {
+        testing_env!(get_owner_context("owner_near"));
+        let name = "test-package";
+        let cid = "QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB".to_string();
+        let reason = "hosts malware".to_string();
+
+        let mut contract = Contract::new("owner_near".parse().unwrap());
+        contract.block_cid(cid.clone(), reason.clone());
+        assert_eq!(contract.is_cid_blocked(cid.clone()), Some(reason.clone()));
+        assert_eq!(try_publish(&mut contract, name, "0.0.1"), Err(RegistryError::BlockedCid(reason)));
 
+        contract.unblock_cid(cid.clone());
+        assert_eq!(contract.is_cid_blocked(cid), None);
+        assert_eq!(try_publish(&mut contract, name, "0.0.1"), Ok(()));
+    }
+
+    #[test]
+    fn reject_update_to_blocked_cid() {
+        testing_env!(get_owner_context("owner_near"));
+        let name = "test-package".to_string();
+        let blocked = "QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n".to_string();
+
+        let mut contract = Contract::new("owner_near".parse().unwrap());
+        publish_versions(&mut contract, &name, &["0.0.1"]);
+        contract.block_cid(blocked.clone(), "hosts malware".to_string());
+
+        let result = contract.update_manifest(name, "0.0.1".to_string(), "ipfs".to_string(), blocked);
+        assert_eq!(result, Err(RegistryError::BlockedCid("hosts malware".to_string())));
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the owner can call this method")]
+    fn non_owner_cannot_block_cid() {
+        testing_env!(get_owner_context("owner_near"));
+        let mut contract = Contract::new("owner_near".parse().unwrap());
+
+        testing_env!(get_owner_context("bob_near"));
+        contract.block_cid("QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB".to_string(), "hosts malware".to_string());
+    }
 
+    #[test]
+    fn publish_and_attest_in_one_call() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+        let author = context.signer_account_id.clone();
+        let name = "test-package".to_string();
         let attestation = "QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n".to_string();
 
-        contract.create_attestation(name.clone(), context.signer_account_id.clone(), attestation.clone());
+        let mut contract = self_attesting_contract();
+        contract.publish_and_attest(
+            name.clone(),
+            "0.0.1".to_string(),
+            "ipfs".to_string(),
+            "QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB".to_string(),
+            attestation.clone(),
+            "build-provenance".to_string()
+        ).unwrap();
+
+        let manifest = contract.try_get_manifest(author.clone(), name.clone(), "0.0.1".to_string()).unwrap();
+        assert_eq!(manifest.cid, "QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB".to_string());
+        let attested = contract.get_attestation(author.clone(), name, author, 0).unwrap();
+        assert_eq!(attested.cid, attestation);
+        assert_eq!(attested.claim_type, "build-provenance".to_string());
+    }
+
+    #[test]
+    fn count_packages_and_manifests() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+
+        let mut contract = Contract::default();
+        publish_versions(&mut contract, "first-package", &["0.0.1", "0.0.2", "0.0.3"]);
+        publish_versions(&mut contract, "second-package", &["0.0.1"]);
+
+        assert_eq!(contract.total_packages(), 2);
+        assert_eq!(contract.total_manifests(), 4);
+
+        contract.delete_manifest("second-package".to_string(), "0.0.1".to_string()).unwrap();
+        contract.delete_manifest("first-package".to_string(), "0.0.2".to_string()).unwrap();
+
+        assert_eq!(contract.total_packages(), 1);
+        assert_eq!(contract.total_manifests(), 2);
+    }
+
+    #[test]
+    fn latest_manifest_skips_yanked_versions() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+        let author = context.signer_account_id.clone();
+        let name = "test-package".to_string();
+
+        let mut contract = Contract::default();
+        contract.batch_create_manifests(
+            name.clone(),
+            vec![
+                ("0.0.1".to_string(), "ipfs".to_string(), "QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB".to_string()),
+                ("0.0.2".to_string(), "ipfs".to_string(), "QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n".to_string())
+            ],
+            false
+        ).unwrap();
+        contract.set_yanked(name.clone(), "0.0.2".to_string(), true).unwrap();
 
         assert_eq!(
-            contract.get_attestation(context.signer_account_id.clone(), name.clone(), context.signer_account_id.clone(), 0).cid,
-            attestation.clone()
+            contract.get_latest_manifest(author.clone(), name.clone()).unwrap(),
+            "QmPK1s3pNYLi9ERiq3BDxKa4XosgWwFRQUydHUtz4YgpqB".to_string()
+        );
+        assert!(contract.try_get_manifest(author.clone(), name.clone(), "0.0.2".to_string()).unwrap().yanked);
+
+        contract.set_yanked(name.clone(), "0.0.1".to_string(), true).unwrap();
+        assert_eq!(
+            contract.get_latest_manifest(author, name).unwrap(),
+            "QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n".to_string()
+        );
+    }
+
+    #[test]
+    fn resolve_version_status() {
+        let context = get_context(false);
+        testing_env!(context.clone());
+        let author = context.signer_account_id.clone();
+        let name = "test-package".to_string();
+
+        let mut contract = Contract::default();
+        publish_versions(&mut contract, &name, &["0.0.1", "0.0.2"]);
+        contract.set_yanked(name.clone(), "0.0.2".to_string(), true).unwrap();
+
+        assert_eq!(contract.resolve_status(author.clone(), name.clone(), "0.0.1".to_string()), VersionStatus::Active);
+        assert_eq!(contract.resolve_status(author.clone(), name.clone(), "0.0.2".to_string()), VersionStatus::Yanked);
+        assert_eq!(contract.resolve_status(author.clone(), name.clone(), "0.0.3".to_string()), VersionStatus::NotFound);
+        assert_eq!(
+            contract.resolve_status(author.clone(), "other-package".to_string(), "0.0.1".to_string()),
+            VersionStatus::NotFound
         );
+        assert_eq!(
+            contract.resolve_status("alice_near".parse().unwrap(), name.clone(), "0.0.1".to_string()),
+            VersionStatus::NotFound
+        );
+
+        // Unyanking makes the version active again
+        contract.set_yanked(name.clone(), "0.0.2".to_string(), false).unwrap();
+        assert_eq!(contract.resolve_status(author, name, "0.0.2".to_string()), VersionStatus::Active);
+    }
+
+    fn setup_paged_packages() -> (Contract, AccountId) {
+        let context = get_context(false);
+        testing_env!(context.clone());
+
+        let mut contract = Contract::default();
+        for name in ["package-a", "package-b", "package-c", "package-d", "package-e"] {
+            publish_versions(&mut contract, name, &["0.0.1"]);
+        }
+
+        return (contract, context.signer_account_id.clone());
+    }
+
+    #[test]
+    fn package_order_survives_removal() {
+        let (mut contract, author) = setup_paged_packages();
+        contract.remove_package("package-b".to_string()).unwrap();
+
+        assert_eq!(contract.get_packages(author.clone()), vec!["package-a", "package-c", "package-d", "package-e"]);
+        assert_eq!(contract.get_packages_paged(author.clone(), 1, 2), vec!["package-c", "package-d"]);
+
+        // A republished name goes to the end
+        publish_versions(&mut contract, "package-b", &["0.0.2"]);
+        assert_eq!(contract.get_packages_paged(author, 3, 2), vec!["package-e", "package-b"]);
+    }
+
+    #[test]
+    fn get_first_package_page() {
+        let (contract, author) = setup_paged_packages();
+
+        assert_eq!(contract.get_packages_paged(author, 0, 2), vec!["package-a", "package-b"]);
+    }
+
+    #[test]
+    fn get_offset_package_page() {
+        let (contract, author) = setup_paged_packages();
+
+        assert_eq!(contract.get_packages_paged(author.clone(), 3, 2), vec!["package-d", "package-e"]);
+        assert_eq!(contract.get_packages_paged(author, 4, 100), vec!["package-e"]);
+    }
+
+    #[test]
+    fn get_package_page_past_end() {
+        let (contract, author) = setup_paged_packages();
+
+        assert!(contract.get_packages_paged(author, 10, 2).is_empty());
     }
 }
@@ -0,0 +1,153 @@
+use near_sdk::borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::collections::LookupMap;
+use near_sdk::env::sha256;
+
+// Prefixes hashed before leaves and interior nodes, so a leaf can never be passed off as a node or the reverse
+const LEAF_DOMAIN: u8 = 0x00;
+const NODE_DOMAIN: u8 = 0x01;
+
+// Leaves are the sha256 of the leaf domain followed by a borsh encoded manifest with its download count zeroed
+pub fn hash_leaf(bytes: &[u8]) -> Vec<u8> {
+    let mut data = vec![LEAF_DOMAIN];
+    data.extend_from_slice(bytes);
+    return sha256(&data);
+}
+
+// Interior nodes are the sha256 of the node domain followed by the left child and the right child
+pub fn hash_pair(left: &[u8], right: &[u8]) -> Vec<u8> {
+    let mut data = vec![NODE_DOMAIN];
+    data.extend_from_slice(left);
+    data.extend_from_slice(right);
+    return sha256(&data);
+}
+
+// The number of nodes on each level of a tree over leaf_count leaves, from the leaves up to the root
+// Nodes are paired left to right and an unpaired last node is promoted unchanged
+fn level_widths(leaf_count: u64) -> Vec<u64> {
+    let mut widths = Vec::new();
+    if leaf_count == 0 {
+        return widths;
+    }
+
+    widths.push(leaf_count);
+    while *widths.last().unwrap() > 1 {
+        widths.push((widths.last().unwrap() + 1) / 2);
+    }
+
+    return widths;
+}
+
+// A merkle tree that keeps every node in storage, so changing the last leaf or appending one only rehashes
+// the path to the root instead of every leaf
+#[derive(BorshSerialize, BorshDeserialize)]
+#[borsh(crate = "near_sdk::borsh")]
+pub struct MerkleTree {
+    leaf_count: u64,
+    // Hashes keyed by (level, position), leaves are on level zero
+    nodes: LookupMap<(u32, u64), Vec<u8>>
+}
+
+impl MerkleTree {
+    pub fn new(prefix: Vec<u8>) -> Self {
+        return Self {
+            leaf_count: 0,
+            nodes: LookupMap::new(prefix)
+        };
+    }
+
+    // The root over every leaf, an empty tree has an empty root
+    pub fn root(&self) -> Vec<u8> {
+        let height = level_widths(self.leaf_count).len();
+        if height == 0 {
+            return Vec::new();
+        }
+
+        return self.nodes.get(&(height as u32 - 1, 0)).unwrap();
+    }
+
+    // Adds a leaf after the last one
+    pub fn push(&mut self, leaf: Vec<u8>) {
+        self.leaf_count += 1;
+        self.set_leaf(self.leaf_count - 1, leaf);
+    }
+
+    // Replaces an existing leaf and rehashes the nodes on its path to the root
+    pub fn set_leaf(&mut self, index: u64, leaf: Vec<u8>) {
+        let widths = level_widths(self.leaf_count);
+        let mut position = index;
+        let mut node = leaf;
+        self.nodes.insert(&(0, position), &node);
+
+        for (level, width) in widths.iter().enumerate().take(widths.len() - 1) {
+            let sibling = position ^ 1;
+            if sibling < *width {
+                let other = self.nodes.get(&(level as u32, sibling)).unwrap();
+                node = match sibling < position {
+                    true => hash_pair(&other, &node),
+                    false => hash_pair(&node, &other),
+                };
+            }
+
+            position /= 2;
+            self.nodes.insert(&(level as u32 + 1, position), &node);
+        }
+    }
+
+    // Drops the last leaf along with the nodes that only covered it, then rehashes the new last leaf's path
+    pub fn pop(&mut self) {
+        if self.leaf_count == 0 {
+            return;
+        }
+
+        let old_widths = level_widths(self.leaf_count);
+        self.leaf_count -= 1;
+        let new_widths = level_widths(self.leaf_count);
+
+        for (level, width) in old_widths.iter().enumerate() {
+            let kept = new_widths.get(level).copied().unwrap_or(0);
+            for position in kept..*width {
+                self.nodes.remove(&(level as u32, position));
+            }
+        }
+
+        if self.leaf_count > 0 {
+            let last = self.leaf_count - 1;
+            let leaf = self.nodes.get(&(0, last)).unwrap();
+            self.set_leaf(last, leaf);
+        }
+    }
+
+    // Removes every node, linear in the number of leaves
+    pub fn clear(&mut self) {
+        for (level, width) in level_widths(self.leaf_count).into_iter().enumerate() {
+            for position in 0..width {
+                self.nodes.remove(&(level as u32, position));
+            }
+        }
+
+        self.leaf_count = 0;
+    }
+
+    // Collects the sibling hashes from a leaf up to the root, flagged true when the sibling sits on the left
+    // Levels where the node is promoted without a sibling contribute no step
+    pub fn proof(&self, index: u64) -> Vec<(Vec<u8>, bool)> {
+        let widths = level_widths(self.leaf_count);
+        let mut steps = Vec::new();
+        let mut position = index;
+
+        for (level, width) in widths.iter().enumerate().take(widths.len().saturating_sub(1)) {
+            let sibling = position ^ 1;
+            if sibling < *width {
+                steps.push((self.nodes.get(&(level as u32, sibling)).unwrap(), sibling < position));
+            }
+
+            position /= 2;
+        }
+
+        return steps;
+    }
+}
+
+pub fn to_hex(bytes: &[u8]) -> String {
+    return bytes.iter().map(|b| format!("{:02x}", b)).collect();
+}
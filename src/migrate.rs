@@ -0,0 +1,163 @@
+use near_sdk::borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::collections::{LookupMap, UnorderedMap};
+use near_sdk::near_bindgen;
+use near_sdk::{env, require, AccountId, PublicKey};
+
+use crate::{Attestation, Contract, ContractExt, Manifest, Namespace, PackageName, PrefixKeys, Types};
+
+// Manifest layout deployed before timestamps, encryption metadata and yanking were added
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug)]
+#[borsh(crate = "near_sdk::borsh")]
+pub struct OldManifest {
+    pub version: String,
+    pub cid: String,
+    pub content_type: String,
+    pub types: Vec<String>
+}
+
+// Attestation layout deployed before notes and liveness challenges were added
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug)]
+#[borsh(crate = "near_sdk::borsh")]
+pub struct OldAttestation {
+    pub pubkey: PublicKey,
+    pub cid: String
+}
+
+// Contract layout deployed before per-account storage prefixes
+// Every author's releases were written under PrefixKeys::Manifest and every attestor's bucket under PrefixKeys::Attestation
+#[derive(BorshSerialize, BorshDeserialize)]
+#[borsh(crate = "near_sdk::borsh")]
+pub struct OldContract {
+    pub packages: LookupMap<AccountId, LookupMap<PackageName, Vec<OldManifest>>>,
+    pub attestations: LookupMap<AccountId, LookupMap<Namespace, Vec<OldAttestation>>>,
+    pub compiled_types: LookupMap<Namespace, Types>,
+    pub type_list: UnorderedMap<String, u8>,
+}
+
+impl From<OldManifest> for Manifest {
+    fn from(old: OldManifest) -> Self {
+        Self {
+            version: old.version,
+            cid: old.cid,
+            content_type: old.content_type,
+            types: old.types,
+            encryption: None,
+            created_at: 0,
+            updated_at: 0,
+            yanked: false,
+            downloads: 0,
+            dependencies: Vec::new()
+        }
+    }
+}
+
+impl From<OldAttestation> for Attestation {
+    fn from(old: OldAttestation) -> Self {
+        Self {
+            pubkey: Some(old.pubkey),
+            identifier: None,
+            cid: old.cid,
+            note: None,
+            reconfirmed_at_ns: 0,
+            claim_type: String::new(),
+            schema_version: String::new(),
+            expires_at: 0
+        }
+    }
+}
+
+#[near_bindgen]
+impl Contract {
+    // Rebuilds the contract struct from the old layout, every field added since then starts empty
+    // Nested manifests and attestations are not enumerable, so they are moved per account with
+    // migrate_packages and migrate_attestations, until then the account reads as having no packages or attestations
+    #[private]
+    #[init(ignore_state)]
+    pub fn migrate(
+        // The account allowed to perform administrative operations
+        owner_id: AccountId
+    ) -> Self {
+        let old: OldContract = env::state_read().expect("No contract state to migrate");
+        let mut contract = Self::default();
+        contract.owner_id = owner_id;
+
+        contract.compiled_types = old.compiled_types;
+        contract.type_list = old.type_list;
+
+        return contract;
+    }
+
+    // Moves an author's packages out of the legacy shared prefix into the author's own storage prefix
+    // Legacy entries are left in place since authors publishing the same name shared a single entry,
+    // packages the author published since the upgrade are kept unless they share a migrated name
+    #[private]
+    pub fn migrate_packages(
+        &mut self,
+        // An account ID of the author who published the packages
+        author: AccountId,
+        // The names of the author's packages to migrate
+        package_names: Vec<PackageName>
+    ) {
+        let legacy: LookupMap<PackageName, Vec<OldManifest>> = LookupMap::new(PrefixKeys::Manifest);
+        let mut releases = LookupMap::new(Self::nested_prefix(PrefixKeys::ManifestV2, &author));
+        self.packages.insert(&author, &releases);
+
+        for package_name in package_names {
+            let old_versions = legacy.get(&package_name);
+            require!(old_versions.is_some(), "Package name not found in legacy storage");
+
+            let versions = old_versions.unwrap()
+                .into_iter()
+                .map(Manifest::from)
+                .collect::<Vec<Manifest>>();
+
+            match releases.get(&package_name) {
+                Some(existing) => {
+                    self.total_manifests -= existing.len() as u64;
+                    self.unindex_cids(&author, &package_name, &existing);
+                }
+                None => self.index_package_name(&author, &package_name),
+            }
+
+            self.total_manifests += versions.len() as u64;
+            self.index_cids(&author, &package_name, &versions);
+            releases.insert(&package_name, &versions);
+
+            let namespace = Self::generate_key(author.clone(), package_name);
+            self.refresh_package_root(&namespace, &versions);
+        }
+    }
+
+    // Moves an attestor's attestations out of the legacy shared prefix into the attestor's own storage prefix
+    #[private]
+    pub fn migrate_attestations(
+        &mut self,
+        // The account that made the attestations
+        attestor: AccountId,
+        // The author and package name of every package the attestor made claims against
+        packages: Vec<(AccountId, PackageName)>
+    ) {
+        let legacy: LookupMap<Namespace, Vec<OldAttestation>> = LookupMap::new(PrefixKeys::Attestation);
+        let mut at = LookupMap::new(Self::nested_prefix(PrefixKeys::AttestorAttestations, &attestor));
+        self.attestations.insert(&attestor, &at);
+
+        for (author, package_name) in packages {
+            let namespace = Self::generate_key(author.clone(), package_name);
+            let old_attestations = legacy.get(&namespace);
+            require!(old_attestations.is_some(), "Attestations not found in legacy storage");
+
+            let attestations = old_attestations.unwrap()
+                .into_iter()
+                .map(Attestation::from)
+                .collect::<Vec<Attestation>>();
+
+            // Migrating the same bucket twice replaces it, so its previous length is taken back out of the total
+            let replaced = at.get(&namespace).map_or(0, |existing| existing.len() as u64);
+            let total = self.author_attestations.get(&author).unwrap_or(0);
+            self.author_attestations.insert(&author, &(total - replaced + attestations.len() as u64));
+
+            at.insert(&namespace, &attestations);
+            self.index_attestor(&namespace, &attestor);
+        }
+    }
+}
@@ -0,0 +1,234 @@
+use std::cmp::Ordering;
+
+// A single dot separated identifier inside of a prerelease tag
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Identifier {
+    Numeric(u64),
+    AlphaNumeric(String)
+}
+
+impl Ord for Identifier {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Numeric identifiers always have lower precedence than alphanumeric ones
+        match (self, other) {
+            (Identifier::Numeric(a), Identifier::Numeric(b)) => a.cmp(b),
+            (Identifier::Numeric(_), Identifier::AlphaNumeric(_)) => Ordering::Less,
+            (Identifier::AlphaNumeric(_), Identifier::Numeric(_)) => Ordering::Greater,
+            (Identifier::AlphaNumeric(a), Identifier::AlphaNumeric(b)) => a.cmp(b),
+        }
+    }
+}
+
+impl PartialOrd for Identifier {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+// A parsed semantic version following the major.minor.patch[-prerelease][+build] format
+// Build metadata is validated but ignored when determining precedence
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Version {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+    pub pre: Vec<Identifier>
+}
+
+impl Version {
+    pub fn parse(input: &str) -> Option<Version> {
+        let (rest, build) = match input.split_once('+') {
+            Some((rest, build)) => (rest, Some(build)),
+            None => (input, None),
+        };
+
+        if let Some(build) = build {
+            if !build.split('.').all(Self::is_valid_identifier) {
+                return None;
+            }
+        }
+
+        let (core, pre) = match rest.split_once('-') {
+            Some((core, pre)) => (core, Some(pre)),
+            None => (rest, None),
+        };
+
+        let parts: Vec<&str> = core.split('.').collect();
+        if parts.len() != 3 {
+            return None;
+        }
+
+        let major = Self::parse_numeric(parts[0])?;
+        let minor = Self::parse_numeric(parts[1])?;
+        let patch = Self::parse_numeric(parts[2])?;
+
+        let mut identifiers = Vec::new();
+        if let Some(pre) = pre {
+            for part in pre.split('.') {
+                if !Self::is_valid_identifier(part) {
+                    return None;
+                }
+
+                if part.bytes().all(|b| b.is_ascii_digit()) {
+                    identifiers.push(Identifier::Numeric(Self::parse_numeric(part)?));
+                } else {
+                    identifiers.push(Identifier::AlphaNumeric(part.to_string()));
+                }
+            }
+        }
+
+        return Some(Version {
+            major,
+            minor,
+            patch,
+            pre: identifiers
+        });
+    }
+
+    // Numeric components must be digits only without leading zeroes
+    fn parse_numeric(part: &str) -> Option<u64> {
+        if part.is_empty() || !part.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+
+        if part.len() > 1 && part.starts_with('0') {
+            return None;
+        }
+
+        return part.parse::<u64>().ok();
+    }
+
+    fn is_valid_identifier(part: &str) -> bool {
+        return !part.is_empty() && part.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-');
+    }
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let core = (self.major, self.minor, self.patch).cmp(&(other.major, other.minor, other.patch));
+        if core != Ordering::Equal {
+            return core;
+        }
+
+        // A version without a prerelease tag has a higher precedence than one with it
+        match (self.pre.is_empty(), other.pre.is_empty()) {
+            (true, true) => Ordering::Equal,
+            (true, false) => Ordering::Greater,
+            (false, true) => Ordering::Less,
+            (false, false) => self.pre.cmp(&other.pre),
+        }
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Operator {
+    Caret,
+    Tilde,
+    AtLeast,
+    Exact
+}
+
+// A basic version requirement: ^, ~, >= or an exact version, optionally prefixed with =
+// Caret, tilde and exact requirements may leave out the minor and patch components, e.g. ^0.1 or ~1
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Requirement {
+    operator: Operator,
+    version: Version,
+    // How many of major, minor and patch were given
+    parts: usize
+}
+
+impl Requirement {
+    pub fn parse(input: &str) -> Option<Requirement> {
+        let input = input.trim();
+        let (operator, rest) = if let Some(rest) = input.strip_prefix('^') {
+            (Operator::Caret, rest)
+        } else if let Some(rest) = input.strip_prefix('~') {
+            (Operator::Tilde, rest)
+        } else if let Some(rest) = input.strip_prefix(">=") {
+            (Operator::AtLeast, rest)
+        } else if let Some(rest) = input.strip_prefix('=') {
+            (Operator::Exact, rest)
+        } else {
+            (Operator::Exact, input)
+        };
+
+        let rest = rest.trim();
+        if let Some(version) = Version::parse(rest) {
+            return Some(Requirement {
+                operator,
+                version,
+                parts: 3
+            });
+        }
+
+        let parts: Vec<&str> = rest.split('.').collect();
+        if parts.len() > 2 || operator == Operator::AtLeast {
+            return None;
+        }
+
+        let major = Version::parse_numeric(parts[0])?;
+        let minor = match parts.get(1) {
+            Some(part) => Version::parse_numeric(part)?,
+            None => 0,
+        };
+
+        return Some(Requirement {
+            operator,
+            version: Version {
+                major,
+                minor,
+                patch: 0,
+                pre: Vec::new()
+            },
+            parts: parts.len()
+        });
+    }
+
+    pub fn matches(&self, version: &Version) -> bool {
+        let core = (version.major, version.minor, version.patch);
+        let required = (self.version.major, self.version.minor, self.version.patch);
+
+        // Prereleases only satisfy requirements naming a prerelease of the same major.minor.patch
+        if !version.pre.is_empty() && (self.version.pre.is_empty() || core != required) {
+            return false;
+        }
+
+        return match self.operator {
+            Operator::Exact if self.parts == 3 => *version == self.version,
+            Operator::Exact => version.major == self.version.major && (self.parts < 2 || version.minor == self.version.minor),
+            Operator::AtLeast => *version >= self.version,
+            Operator::Caret | Operator::Tilde => *version >= self.version && self.upper_bound().map_or(true, |bound| core < bound),
+        };
+    }
+
+    // The exclusive major.minor.patch bound of caret and tilde requirements
+    // None when the bumped component would overflow, since no version can then reach the bound
+    fn upper_bound(&self) -> Option<(u64, u64, u64)> {
+        let Version { major, minor, patch, .. } = self.version;
+
+        if self.operator == Operator::Tilde {
+            return match self.parts {
+                1 => major.checked_add(1).map(|major| (major, 0, 0)),
+                _ => minor.checked_add(1).map(|minor| (major, minor, 0)),
+            };
+        }
+
+        // Caret allows changes that do not modify the left-most non-zero component that was given
+        if major > 0 || self.parts == 1 {
+            return major.checked_add(1).map(|major| (major, 0, 0));
+        }
+
+        if minor > 0 || self.parts == 2 {
+            return minor.checked_add(1).map(|minor| (0, minor, 0));
+        }
+
+        return patch.checked_add(1).map(|patch| (0, 0, patch));
+    }
+}